@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// The answer to a part of a puzzle: usually a number, sometimes too big for an `i64`, sometimes
+/// text.
+///
+/// Numbers are kept as [`Answer::Int`] when they fit, so equal numbers are equal answers whatever
+/// type they were computed in:
+///
+/// ```
+/// use aoc2023::Answer;
+///
+/// assert_eq!(Answer::from(6440i128), Answer::Int(6440));
+/// assert_eq!(Answer::from(i64::MAX as i128 * 2).to_string(), "18446744073709551614");
+/// assert_eq!(Answer::parse("LJKRA"), Answer::Text("LJKRA".to_string()));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Int(i64),
+    /// A number that doesn't fit in an `i64`.
+    Big(i128),
+    Text(String),
+}
+
+impl Answer {
+    /// Read an answer back from its [`Display`](fmt::Display) form.
+    pub fn parse(answer: &str) -> Answer {
+        if let Ok(number) = answer.parse::<i128>() {
+            Answer::from(number)
+        } else {
+            Answer::Text(answer.to_string())
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(number) => number.fmt(f),
+            Answer::Big(number) => number.fmt(f),
+            Answer::Text(text) => f.pad(text),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(number: i32) -> Answer {
+        Answer::Int(number as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(number: i64) -> Answer {
+        Answer::Int(number)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Answer {
+        Answer::from(number as i128)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(number: u64) -> Answer {
+        Answer::from(number as i128)
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(number: i128) -> Answer {
+        match i64::try_from(number) {
+            Ok(number) => Answer::Int(number),
+            Err(_) => Answer::Big(number),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Answer::parse("-42"), Answer::Int(-42));
+        assert_eq!(Answer::parse("18446744073709551614"), Answer::Big(18446744073709551614));
+        assert_eq!(Answer::parse("6440 points"), Answer::Text("6440 points".to_string()));
+        for answer in [Answer::Int(35), Answer::Big(i128::MIN), Answer::from("EFKLZ")] {
+            assert_eq!(Answer::parse(&answer.to_string()), answer);
+        }
+    }
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(35i32), Answer::Int(35));
+        assert_eq!(Answer::from(u64::MAX), Answer::Big(u64::MAX as i128));
+        assert_eq!(Answer::from(i64::MIN as i128), Answer::Int(i64::MIN));
+    }
+}
@@ -1,4 +1,7 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::error::Error;
+use crate::solver::{Example, Part, Solver};
+use crate::trace;
 
 /// # Examples:
 ///
@@ -17,28 +20,30 @@ use std::fs;
 /// ```
 /// assert_eq!(aoc2023::aoc::day_01::calibrate("treb7uchet"), 77);
 /// ```
-pub fn calibrate(line: &str) -> i32 {
-    let digits: Vec<i32> = line.chars()
+pub fn calibrate(line: &str) -> i64 {
+    let digits: Vec<i64> = line.chars()
         .filter(|c| c.is_numeric())
-        .map(|c| c.to_digit(10).unwrap() as i32)
+        .map(|c| i64::from(c.to_digit(10).unwrap()))
         .collect();
 
-    10 * digits.first().unwrap_or(&0)
-        + digits.last().unwrap_or(&0)
+    let result = 10 * digits.first().unwrap_or(&0)
+        + digits.last().unwrap_or(&0);
+    trace::explain(|| format!("{}: digits {:?} -> {}", line, digits, result));
+    result
 }
 
 /// Solve AOC2023 day 1, Part One.
 /// https://adventofcode.com/2023/day/1
-pub fn solve_part_1(input: &str) -> i32 {
-    return match fs::read_to_string(input) {
-        Ok(contents) => {
-            contents.lines().map(|line| calibrate(line)).sum()
-        }
-        Err(error) => {
-            println!("Error: {}", error);
-            0
-        }
-    };
+///
+/// # Examples:
+///
+/// ```
+/// use aoc2023::aoc::day_01::{parse, solve_part_1};
+/// let document = parse("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet");
+/// assert_eq!(solve_part_1(&document), 142);
+/// ```
+pub fn solve_part_1(document: &[String]) -> i64 {
+    document.iter().map(|line| calibrate(line)).sum()
 }
 
 /// Calibrate with words in addition to digits.
@@ -75,50 +80,101 @@ pub fn solve_part_1(input: &str) -> i32 {
 /// ```
 /// assert_eq!(aoc2023::aoc::day_01::calibrate_words("262"), 22);
 /// ```
-pub fn calibrate_words(line: &str) -> i32 {
+pub fn calibrate_words(line: &str) -> i64 {
     let words = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
-    let digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let digits = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
     // rewrite using matches to find all possible positions for a single digit/word
-    let mut digit_positions: std::collections::HashMap<usize, i32> = std::collections::HashMap::new();
-    for (index, word) in words.iter().enumerate() {
-        line.match_indices(word)
-            .for_each(|(position, _)| {
-                digit_positions.insert(position, (index + 1) as i32);
-            });
-    }
-    for digit in digits.iter() {
-        line.match_indices(&digit.to_string())
-            .for_each(|(position, _)| {
-                digit_positions.insert(position, *digit);
-            });
+    let mut digit_positions: std::collections::HashMap<usize, (i64, &str)> = std::collections::HashMap::new();
+    for tokens in [words, digits] {
+        for (digit, token) in (1..).zip(tokens.iter()) {
+            line.match_indices(token)
+                .for_each(|(position, _)| {
+                    digit_positions.insert(position, (digit, token));
+                });
+        }
     }
 
 
     // find the lowest and highest digit positions
     let left_most = digit_positions.keys()
         .min()
-        .map(|k| digit_positions.get(k).unwrap_or(&0));
+        .map(|k| (k, digit_positions[k]));
     let right_most = digit_positions.keys()
         .max()
-        .map(|k| digit_positions.get(k).unwrap_or(&0));
+        .map(|k| (k, digit_positions[k]));
 
-
-    let result = 10 * left_most.unwrap_or(&0) + right_most.unwrap_or(&0);
-    // println!("{} -> {}", line, result);
+    let result = 10 * left_most.map_or(0, |(_, (digit, _))| digit) + right_most.map_or(0, |(_, (digit, _))| digit);
+    trace::explain(|| match (left_most, right_most) {
+        (Some((left, (_, left_token))), Some((right, (_, right_token)))) => {
+            format!("{}: first `{}` at {}, last `{}` at {} -> {}", line, left_token, left, right_token, right, result)
+        }
+        _ => format!("{}: no digits -> 0", line),
+    });
     return result;
 }
 
 /// Solve AOC2023 day 1, Part Two.
 /// https://adventofcode.com/2023/day/1#part2
-pub fn solve_part_2(input: &str) -> i32 {
-    return match fs::read_to_string(input) {
-        Ok(contents) => {
-            contents.lines().map(|line| calibrate_words(line)).sum()
-        }
-        Err(error) => {
-            println!("Error: {}", error);
-            0
-        }
-    };
-}
\ No newline at end of file
+///
+/// # Examples:
+///
+/// ```
+/// use aoc2023::aoc::day_01::{parse, solve_part_2};
+/// let document = parse("two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen");
+/// assert_eq!(solve_part_2(&document), 281);
+/// ```
+pub fn solve_part_2(document: &[String]) -> i64 {
+    document.iter().map(|line| calibrate_words(line)).sum()
+}
+
+/// Split the calibration document into its lines.
+pub fn parse(input: &str) -> Vec<String> {
+    input.lines().map(String::from).collect()
+}
+
+/// Example from part one of the puzzle description.
+pub const TEST_DOCUMENT: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+/// Example from part two of the puzzle description.
+pub const TEST_DOCUMENT_WORDS: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_DOCUMENT, answer: "142" },
+    Example { part: Part::Two, input: TEST_DOCUMENT_WORDS, answer: "281" },
+];
+
+/// Solver for AOC2023 day 1.
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input))
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input).into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
@@ -1,82 +1,90 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+use crate::trace;
 
 #[derive(Debug, PartialEq)]
 struct Set {
-    red: i32,
-    green: i32,
-    blue: i32,
+    red: i64,
+    green: i64,
+    blue: i64,
 }
 
-struct Game {
-    id: i32,
+/// A game of revealing cubes from the bag.
+pub struct Game {
+    id: i64,
     sets: Vec<Set>,
 }
 
 struct Bag {
-    red: i32,
-    green: i32,
-    blue: i32,
+    red: i64,
+    green: i64,
+    blue: i64,
 }
 
 fn is_valid(game: &Game, bag: &Bag) -> bool {
-    return game.sets.iter().all(|set|
-        set.red <= bag.red
-            && set.green <= bag.green
-            && set.blue <= bag.blue
-    );
+    first_excess(game, bag).is_none()
 }
 
-/// Build a game from a line of text.
-fn build_game(line: &str) -> Game {
-    let mut game = Game {
-        id: 0,
-        sets: Vec::new(),
-    };
-
-    let mut parts = line.split(": ");
-    game.id = parts.next().unwrap().strip_prefix("Game ").unwrap().parse::<i32>().unwrap();
+/// The first color of a game that shows more cubes than the bag holds: the index of the set, the
+/// color, the count shown and the count in the bag.
+fn first_excess(game: &Game, bag: &Bag) -> Option<(usize, &'static str, i64, i64)> {
+    game.sets.iter().enumerate()
+        .flat_map(|(i, set)| [
+            (i, "red", set.red, bag.red),
+            (i, "green", set.green, bag.green),
+            (i, "blue", set.blue, bag.blue),
+        ])
+        .find(|&(_, _, count, limit)| count > limit)
+}
 
-    let sets = parts.next().unwrap().split("; ");
-    for set_string in sets {
-        let colors = set_string.split(", ");
+/// Build a game from a line of the input.
+fn build_game(input: &str, line: &str) -> Result<Game, ParseError> {
+    let (label, sets) = parse::pair(input, line, ":", "\"Game <n>: <sets>\"")?;
+    let id = parse::labeled_number(input, label, "Game")?;
+    let sets = parse::list(sets, ";", |set_string| {
         let mut set = Set {
             red: 0,
             green: 0,
             blue: 0,
         };
-        for color in colors {
-            let mut color_parts = color.split(' ');
-            let count = color_parts.next().unwrap().parse::<i32>().unwrap();
-            let color = color_parts.next().unwrap();
-            match color {
-                "red" => set.red = count,
-                "green" => set.green = count,
-                "blue" => set.blue = count,
-                _ => panic!("Unknown color {}", color),
+        for cubes in set_string.split(',') {
+            let (count, color) = parse::pair(input, cubes, " ", "\"<n> <color>\"")?;
+            let count = parse::number(input, count)?;
+            match parse::one_of(input, color, &["red", "green", "blue"])? {
+                0 => set.red = count,
+                1 => set.green = count,
+                _ => set.blue = count,
             }
         }
-        game.sets.push(set);
-    }
+        Ok(set)
+    })?;
 
-    game
+    Ok(Game { id, sets })
 }
 
-pub fn solve_part_1(input_file: &str) -> i32 {
+/// Parse the record of games, a game per line.
+pub fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse::lines(input, |line| build_game(input, line))
+}
+
+pub fn solve_part_1(games: &[Game]) -> i64 {
     let bag = Bag { red: 12, green: 13, blue: 14 };
 
-    return match fs::read_to_string(input_file) {
-        Ok(contents) => {
-            contents.lines()
-                .map(build_game)
-                .filter(|game| is_valid(game, &bag))
-                .map(|game| game.id)
-                .sum()
-        }
-        Err(error) => {
-            println!("Error: {}", error);
-            0
-        }
-    };
+    games.iter()
+        .filter(|game| {
+            trace::explain(|| match first_excess(game, &bag) {
+                None => format!("game {}: possible", game.id),
+                Some((set, color, count, limit)) => {
+                    format!("game {}: impossible, set {} shows {} {} but the bag holds {}",
+                            game.id, set + 1, count, color, limit)
+                }
+            });
+            is_valid(game, &bag)
+        })
+        .map(|game| game.id)
+        .sum()
 }
 
 /// Find the fewest number of cubes of each color that could have been in the bag to make the game
@@ -92,29 +100,65 @@ fn find_min_set(game: &Game) -> Set {
     min_set
 }
 
-fn compute_power(set: &Set) -> i32 {
-    set.red * set.green * set.blue
+/// The power of a set, `None` if it doesn't fit in an `i64`.
+fn compute_power(set: &Set) -> Option<i64> {
+    set.red.checked_mul(set.green)?.checked_mul(set.blue)
 }
 
-pub fn solve_part_2(input_file: &str) -> i32 {
-    return match fs::read_to_string(input_file) {
-        Ok(contents) => {
-            contents.lines()
-                .map(build_game)
-                .map(|game: Game| find_min_set(&game))
-                .map(|set: Set| compute_power(&set))
-                .sum()
-        }
-        Err(error) => {
-            println!("Error: {}", error);
-            0
-        }
-    };
+pub fn solve_part_2(games: &[Game]) -> Result<i64, Error> {
+    games.iter()
+        .try_fold(0_i64, |sum, game| {
+            let set = find_min_set(game);
+            let power = compute_power(&set)
+                .ok_or_else(|| Error::Unsolvable(format!("the power of game {} doesn't fit in 64 bits", game.id)))?;
+            trace::explain(|| format!("game {}: at least {} red, {} green, {} blue, power {}",
+                                      game.id, set.red, set.green, set.blue, power));
+            sum.checked_add(power)
+                .ok_or_else(|| Error::Unsolvable("the sum of the powers doesn't fit in 64 bits".to_string()))
+        })
+}
+
+/// Example from the puzzle description.
+pub const TEST_GAMES: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_GAMES, answer: "8" },
+    Example { part: Part::Two, input: TEST_GAMES, answer: "2286" },
+];
+
+/// Solver for AOC2023 day 2.
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Vec<Game>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input)?.into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_02::{Bag, find_min_set, Game, is_valid, Set};
+    use crate::day_02::{Bag, find_min_set, first_excess, Game, is_valid, parse, Set, solve_part_1, solve_part_2, TEST_GAMES};
+    use crate::error::{Error, ParseError};
 
     const TEST_BAG: Bag = Bag { red: 12, green: 13, blue: 14 };
 
@@ -170,6 +214,18 @@ mod tests {
         assert!(is_valid(&game_5, &TEST_BAG));
     }
 
+    #[test]
+    fn first_excess_works() {
+        let game = Game {
+            id: 3,
+            sets: vec![
+                Set { red: 4, green: 13, blue: 5 },
+                Set { red: 20, green: 8, blue: 6 },
+            ],
+        };
+        assert_eq!(first_excess(&game, &TEST_BAG), Some((1, "red", 20, 12)));
+    }
+
     #[test]
     fn find_min_set_works() {
         let game_1 = Game {
@@ -221,4 +277,45 @@ mod tests {
         };
         assert_eq!(find_min_set(&game_5), Set { red: 6, green: 3, blue: 2 });
     }
+
+    #[test]
+    fn parse_reports_the_bad_color() {
+        let error = parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue; 2 purple").err().unwrap();
+        assert_eq!(error, ParseError {
+            line: 2,
+            column: 19,
+            token: "purple".to_string(),
+            expected: "red, green or blue".to_string(),
+            found: None,
+            text: "Game 2: 1 blue; 2 purple".to_string(),
+        });
+        assert_eq!(parse("Gaem 1: 3 blue").err().unwrap().expected, "\"Game <n>\"");
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(solve_part_1(&parse(TEST_GAMES).unwrap()), 8);
+    }
+
+    #[test]
+    fn test_part_1_explained() {
+        let (sum, notes) = crate::trace::collect(true, || solve_part_1(&parse(TEST_GAMES).unwrap()));
+        assert_eq!(sum, 8);
+        assert_eq!(notes[2], "game 3: impossible, set 1 shows 20 red but the bag holds 12");
+        assert_eq!(notes.len(), 5);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(solve_part_2(&parse(TEST_GAMES).unwrap()).unwrap(), 2286);
+    }
+
+    #[test]
+    fn test_powers_too_big() {
+        let games = parse("Game 1: 4000000000 red, 4000000000 green, 1 blue").unwrap();
+        assert!(matches!(solve_part_2(&games), Err(Error::Unsolvable(_))));
+
+        let games = parse("Game 1: 5000000000000000000 red, 1 green, 1 blue\nGame 2: 5000000000000000000 red, 1 green, 1 blue").unwrap();
+        assert!(matches!(solve_part_2(&games), Err(Error::Unsolvable(_))));
+    }
 }
\ No newline at end of file
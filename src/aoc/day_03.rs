@@ -1,129 +1,71 @@
-use std::fs;
-
-/// A top-left - bottom-right position in a schematic.
-#[derive(Debug, PartialEq)]
-struct Position {
-    x: usize, // column
-    y: usize, // row
+use crate::answer::Answer;
+use crate::aoc::grid::{Grid, Position};
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+use crate::trace;
+
+/// An engine schematic: numbers, symbols and `.` for the empty cells.
+pub struct Schematic {
+    grid: Grid<char>,
+    numbers: Vec<Number>,
 }
 
-fn find_part_numbers(schematic: &str) -> Vec<i32> {
-    let mut valid_part_numbers: Vec<i32> = Vec::new();
-    let symbol_positions = find_symbol_positions(schematic);
-
-    let num_lines = schematic.lines().count();
-    for (i, line) in schematic.lines().enumerate() {
-        let seqs = seq_digit_positions(line);
-
-        seqs.iter().for_each(|seq| {
-            let candidate_positions = find_candidate_positions(num_lines, i, &line, seq);
-            if candidate_positions.iter().any(|pos| symbol_positions.contains(pos)) {
-                let number = line[seq[0]..=seq[seq.len() - 1]].parse::<i32>().unwrap();
-                valid_part_numbers.push(number);
-            }
-        });
-    }
-
-    valid_part_numbers
+/// A number of the schematic, with the cells of its digits.
+struct Number {
+    value: i64,
+    digits: Vec<Position>,
 }
 
-fn find_candidate_positions(num_lines: usize, line_index: usize, line: &&str, positions_seq: &Vec<usize>) -> Vec<Position> {
-    let mut candidate_positions: Vec<Position> = Vec::new();
-    if line_index > 0 {
-        // the row above
-        positions_seq.iter().for_each(|pos| {
-            candidate_positions.push(Position { x: *pos, y: line_index - 1 });
-        });
-    }
-    if line_index < num_lines - 1 {
-        // the row below
-        positions_seq.iter().for_each(|pos| {
-            candidate_positions.push(Position { x: *pos, y: line_index + 1 });
-        });
-    }
-    let left_most_position = positions_seq.iter().min().unwrap();
-    if left_most_position > &0 {
-        // the column to the left
-        candidate_positions.push(Position { x: left_most_position - 1, y: line_index });
-        if line_index > 0 {
-            // top-left corner
-            candidate_positions.push(Position { x: left_most_position - 1, y: line_index - 1 });
-        }
-        if line_index < num_lines - 1 {
-            // bottom-left corner
-            candidate_positions.push(Position { x: left_most_position - 1, y: line_index + 1 });
-        }
-    }
-
-    let right_most_position = positions_seq.iter().max().unwrap();
-    if right_most_position < &(line.len() - 1) {
-        // the column to the right
-        candidate_positions.push(Position { x: right_most_position + 1, y: line_index });
-        if line_index > 0 {
-            // top-right corner
-            candidate_positions.push(Position { x: right_most_position + 1, y: line_index - 1 });
-        }
-        if line_index < num_lines - 1 {
-            // bottom-right corner
-            candidate_positions.push(Position { x: right_most_position + 1, y: line_index + 1 });
-        }
-    }
-
-    candidate_positions
+/// Parse the rows of an engine schematic, and the numbers in them.
+pub fn parse(input: &str) -> Result<Schematic, ParseError> {
+    let grid = Grid::parse(input)?;
+    let numbers = grid.runs(char::is_ascii_digit).into_iter()
+        .map(|digits| {
+            let token = digits_in(input, &digits);
+            let value = token.parse::<i64>()
+                .map_err(|_| ParseError::at(input, token, "a number that fits in 64 bits").found("a bigger number"))?;
+            Ok(Number { value, digits })
+        })
+        .collect::<Result<Vec<Number>, ParseError>>()?;
+    Ok(Schematic { grid, numbers })
 }
 
-/// Find all sequential positions with digits in a line.
-fn seq_digit_positions(line: &str) -> Vec<Vec<usize>> {
-    let mut result = Vec::new();
-    let mut seq: Vec<usize> = Vec::new();
-    for (i, c) in line.chars().enumerate() {
-        if c.is_digit(10) {
-            seq.push(i);
-        } else {
-            if !seq.is_empty() {
-                result.push(seq);
-            }
-            seq = Vec::new();
-        }
-    }
-
-    if !seq.is_empty() {
-        result.push(seq);
-    }
-
-    result
+/// The text of a run of digits in the input.
+fn digits_in<'a>(input: &'a str, digits: &[Position]) -> &'a str {
+    let line = input.lines().nth(digits[0].y).unwrap_or(input);
+    let start: usize = line.chars().take(digits[0].x).map(char::len_utf8).sum();
+    &line[start..start + digits.len()]
 }
 
-fn find_symbol_positions(schematic: &str) -> Vec<Position> {
-    let mut result = Vec::new();
+fn is_symbol(cell: &char) -> bool {
+    !cell.is_ascii_digit() && *cell != '.'
+}
 
-    for (y, line) in schematic.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if !c.is_digit(10) && c != '.' {
-                result.push(Position { x, y });
+fn find_part_numbers(schematic: &Schematic) -> Vec<i64> {
+    let grid = &schematic.grid;
+    let mut valid_part_numbers: Vec<i64> = Vec::new();
+
+    for Number { value, digits } in &schematic.numbers {
+        let line = digits[0].y + 1;
+        match grid.border(digits).into_iter().find(|&around| is_symbol(&grid[around])) {
+            Some(symbol) => {
+                trace::explain(|| format!("line {}: {} touches `{}` at line {}, column {}",
+                                          line, value, grid[symbol], symbol.y + 1, symbol.x + 1));
+                valid_part_numbers.push(*value);
             }
+            None => trace::explain(|| format!("line {}: {} touches no symbol", line, value)),
         }
     }
 
-    result
+    valid_part_numbers
 }
 
-pub fn solve_part_1(input_file: &str) -> i32 {
-    return match fs::read_to_string(input_file) {
-        Ok(schematic) => {
-            find_part_numbers(schematic.as_str()).iter().sum()
-        }
-        Err(_) => {
-            0
-        }
-    };
+pub fn solve_part_1(schematic: &Schematic) -> i64 {
+    find_part_numbers(schematic).iter().sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_SCHEMATIC: &str = "\
+/// Example from the puzzle description.
+pub const TEST_SCHEMATIC: &str = "\
 467..114..
 ...*......
 ..35..633.
@@ -135,33 +77,73 @@ mod tests {
 ...$.*....
 .664.598..";
 
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_SCHEMATIC, answer: "4361" },
+];
+
+/// Solver for AOC2023 day 3.
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Schematic;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, _input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(None)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
-    fn test_seq_digit_positions() {
-        assert_eq!(seq_digit_positions("467..114.."), vec![vec![0, 1, 2], vec![5, 6, 7]]);
-        assert_eq!(seq_digit_positions("...*......"), Vec::<Vec<usize>>::new());
-        assert_eq!(seq_digit_positions("..35..633."), vec![vec![2, 3], vec![6, 7, 8]]);
+    fn test_digit_runs() {
+        let grid = Grid::parse("467..114..\n...*......\n..35..633.").unwrap();
+        let numbers: Vec<String> = grid.runs(char::is_ascii_digit).iter()
+            .map(|digits| digits.iter().map(|&digit| grid[digit]).collect())
+            .collect();
+        assert_eq!(numbers, vec!["467", "114", "35", "633"]);
     }
 
     #[test]
     fn test_symbol_positions() {
-        assert_eq!(find_symbol_positions(TEST_SCHEMATIC), vec![
-            Position { x: 3, y: 1 },
-            Position { x: 6, y: 3 },
-            Position { x: 3, y: 4 },
-            Position { x: 5, y: 5 },
-            Position { x: 3, y: 8 },
-            Position { x: 5, y: 8 },
-        ]);
+        let grid = parse(TEST_SCHEMATIC).unwrap().grid;
+        let symbols: Vec<(usize, usize)> = grid.iter()
+            .filter(|(_, cell)| is_symbol(cell))
+            .map(|(position, _)| (position.x, position.y))
+            .collect();
+        assert_eq!(symbols, vec![(3, 1), (6, 3), (3, 4), (5, 5), (3, 8), (5, 8)]);
     }
 
     #[test]
     fn find_part_numbers_example() {
-        assert_eq!(find_part_numbers(TEST_SCHEMATIC), vec![467, 35, 633, 617, 592, 755, 664, 598]);
+        assert_eq!(find_part_numbers(&parse(TEST_SCHEMATIC).unwrap()), vec![467, 35, 633, 617, 592, 755, 664, 598]);
 
     }
     #[test]
     fn test_day3_example() {
-        assert_eq!(find_part_numbers(TEST_SCHEMATIC).iter().sum::<i32>(), 4361);
+        assert_eq!(find_part_numbers(&parse(TEST_SCHEMATIC).unwrap()).iter().sum::<i64>(), 4361);
+        assert_eq!(solve_part_1(&parse(TEST_SCHEMATIC).unwrap()), 4361);
+    }
+
+    #[test]
+    fn test_numbers_too_big() {
+        let error = parse("..467...............\n.*..................\n12345678901234567890").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (3, 1, "12345678901234567890"));
+        assert_eq!(error.found.as_deref(), Some("a bigger number"));
     }
 
     #[test]
@@ -170,7 +152,7 @@ mod tests {
 ....546......*....454...120..683.............923.....@...*...865.574......276........56...../57.659..*................-...-...512...........
 ............329...*.................................606.599...................*927..*.................674..*........723..974................
 ................378..911........987.....606......................899.73....489......848.....................664...............388......589..";
-        assert_eq!(find_part_numbers(rows), vec![454, 56, 57, 329, 606, 599, 927, 674, 723, 974, 378, 489, 848, 664]);
+        assert_eq!(find_part_numbers(&parse(rows).unwrap()), vec![454, 56, 57, 329, 606, 599, 927, 674, 723, 974, 378, 489, 848, 664]);
     }
 
     #[test]
@@ -178,6 +160,6 @@ mod tests {
         let rows = "\
 ............832*105..@........$..................*.........797.....535..932.........*....152...........123.........678.540...........-...6..
 ....&..948..........................271....-....228..79.26.........................733...=...715............27.586........*.......883...*...";
-        assert_eq!(find_part_numbers(rows), vec![832, 105, 152, 540, 6, 228, 733, 883]);
+        assert_eq!(find_part_numbers(&parse(rows).unwrap()), vec![832, 105, 152, 540, 6, 228, 733, 883]);
     }
 }
\ No newline at end of file
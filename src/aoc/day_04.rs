@@ -1,62 +1,77 @@
-use std::fs;
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+use crate::trace;
 
-pub fn solve_part_1(input_file: &str) -> i32 {
-    return match fs::read_to_string(input_file) {
-        Ok(schematic) => { compute_scores(schematic.as_str()).iter().sum() }
-        Err(_) => { 0 }
-    };
+/// A scratchcard with its winning numbers and the numbers on it.
+pub struct Card {
+    winning_numbers: Vec<i64>,
+    numbers: Vec<i64>,
 }
 
-pub fn solve_part_2(input_file: &str) -> i32 {
-    return match fs::read_to_string(input_file) {
-        Ok(schematic) => {
-            fill_copies(schematic.as_str()).len() as i32
-        }
-        Err(_) => { 0 }
-    };
+pub fn solve_part_1(cards: &[Card]) -> Result<i64, Error> {
+    compute_scores(cards)?.into_iter()
+        .try_fold(0_i64, |sum, score| sum.checked_add(score))
+        .ok_or_else(|| Error::Unsolvable("the sum of the scores doesn't fit in 64 bits".to_string()))
+}
+
+pub fn solve_part_2(cards: &[Card]) -> usize {
+    fill_copies(cards).len()
 }
 
+/// Parse the table of scratchcards, a card per line.
+pub fn parse(cards_table: &str) -> Result<Vec<Card>, ParseError> {
+    parse::lines(cards_table, |line| build_card(cards_table, line))
+}
+
+fn build_card(cards_table: &str, line: &str) -> Result<Card, ParseError> {
+    let (card_id, numbers) = parse::pair(cards_table, line, ":", "\"Card <n>: <numbers> | <numbers>\"")?;
+    parse::labeled_number::<usize>(cards_table, card_id, "Card")?;
+    let (winning_numbers, numbers) = parse::pair(cards_table, numbers, "|", "\"<numbers> | <numbers>\"")?;
 
-/// Compute an i32 score for each card in the table.
-fn compute_scores(cards_table: &str) -> Vec<i32> {
-    cards_table.lines()
+    Ok(Card {
+        winning_numbers: parse::numbers(cards_table, winning_numbers)?,
+        numbers: parse::numbers(cards_table, numbers)?,
+    })
+}
+
+/// Compute the score for each card in the table. Fails if a score doesn't fit in an `i64`.
+fn compute_scores(cards: &[Card]) -> Result<Vec<i64>, Error> {
+    cards.iter()
         .map(winning_numbers_in_card)
-        .map(|numbers|numbers.len() as i32)
-        .map(|n| if n > 0 {
-            2i32.pow((n - 1) as u32)
-        } else {
-            0
+        .enumerate()
+        .map(|(i, numbers)| {
+            let n = numbers.len();
+            let score = if n > 0 {
+                u32::try_from(n - 1).ok().and_then(|doublings| 2_i64.checked_pow(doublings))
+            } else {
+                Some(0)
+            };
+            let score = score
+                .ok_or_else(|| Error::Unsolvable(format!("the score of card {} doesn't fit in 64 bits", i + 1)))?;
+            trace::explain(|| format!("card {}: {} winning numbers {:?}, worth {}", i + 1, n, numbers, score));
+            Ok(score)
         })
         .collect()
 }
 
-fn winning_numbers_in_card(card: &str) -> Vec<i32> {
-    let (card_id, numbers) = card.split_once(':').unwrap();
-    let (winning_numbers, card_numbers) = numbers.split_once('|').unwrap();
-
-    let winning_numbers = winning_numbers
-        .trim()
-        .split_whitespace()
-        .map(|n| n.parse::<i32>().unwrap()).collect::<Vec<i32>>();
-    let card_numbers = card_numbers
-        .trim()
-        .split_whitespace()
-        .map(|n| n.parse::<i32>().unwrap()).collect::<Vec<i32>>();
-
-    card_numbers.into_iter()
-        .filter(|n| winning_numbers.contains(n))
-        .into_iter().collect::<Vec<i32>>()
+fn winning_numbers_in_card(card: &Card) -> Vec<i64> {
+    card.numbers.iter()
+        .copied()
+        .filter(|n| card.winning_numbers.contains(n))
+        .collect::<Vec<i64>>()
 }
 
-fn fill_copies(cards_table: &str) -> Vec<usize> {
-    let mut indices: Vec<usize> = (0usize..cards_table.lines().count()).collect();
+fn fill_copies(cards: &[Card]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0usize..cards.len()).collect();
 
-    let num_winning_in_card: Vec<usize> = cards_table.lines()
+    let num_winning_in_card: Vec<usize> = cards.iter()
         .map(winning_numbers_in_card)
         .map(|numbers| numbers.len() as usize)
         .collect();
 
-    for (card_id, numbers) in num_winning_in_card.into_iter().enumerate() {
+    for (card_id, &numbers) in num_winning_in_card.iter().enumerate() {
         for j in card_id +1..=(card_id + numbers) {
             let duplicates = indices.iter().filter(|&k| k == &card_id).count();
             (0..duplicates).for_each(|_k| indices.push(j));
@@ -64,14 +79,17 @@ fn fill_copies(cards_table: &str) -> Vec<usize> {
     }
 
     indices.sort();
+    for (card_id, numbers) in num_winning_in_card.into_iter().enumerate() {
+        trace::explain(|| {
+            let copies = indices.iter().filter(|&&k| k == card_id).count();
+            format!("card {}: {} winning numbers, {} copies", card_id + 1, numbers, copies)
+        });
+    }
     indices
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_CARDS_TABLE: &str = "\
+/// Example from the puzzle description.
+pub const TEST_CARDS_TABLE: &str = "\
 Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
@@ -79,13 +97,76 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_CARDS_TABLE, answer: "13" },
+    Example { part: Part::Two, input: TEST_CARDS_TABLE, answer: "30" },
+];
+
+/// Solver for AOC2023 day 4.
+pub struct Day04;
+
+impl Solver for Day04 {
+    type Input = Vec<Card>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input)?.into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input).into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_compute_scores() {
-        assert_eq!(compute_scores(TEST_CARDS_TABLE), vec![8, 2, 2, 1, 0, 0]);
+        assert_eq!(compute_scores(&parse(TEST_CARDS_TABLE).unwrap()).unwrap(), vec![8, 2, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_scores_too_big() {
+        // 63 winning numbers score 2^62, 64 of them 2^63
+        let numbers: Vec<String> = (1..=64).map(|n| n.to_string()).collect();
+        let card = |n: usize| format!("Card 1: {} | {}", numbers[..n].join(" "), numbers[..n].join(" "));
+        assert_eq!(compute_scores(&parse(&card(63)).unwrap()).unwrap(), vec![1 << 62]);
+        assert!(matches!(compute_scores(&parse(&card(64)).unwrap()), Err(Error::Unsolvable(_))));
+
+        let cards = format!("{}\n{}", card(63), card(63).replace("Card 1", "Card 2"));
+        assert!(matches!(solve_part_1(&parse(&cards).unwrap()), Err(Error::Unsolvable(_))));
     }
 
     #[test]
     fn test_fill_copies() {
-        assert_eq!(fill_copies(TEST_CARDS_TABLE).iter().count(), 30);
+        assert_eq!(fill_copies(&parse(TEST_CARDS_TABLE).unwrap()).iter().count(), 30);
+    }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(solve_part_1(&parse(TEST_CARDS_TABLE).unwrap()).unwrap(), 13);
+        assert_eq!(solve_part_2(&parse(TEST_CARDS_TABLE).unwrap()), 30);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("Card 1: 41 48 | 83 86\nCrad 2: 13 32 | 61 30").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 1, "Crad 2"));
+        assert_eq!(error.expected, "\"Card <n>\"");
+
+        let error = parse("Card 1: 41 48 | 83 8x6").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 20, "8x6"));
+        assert!(parse("Card 1: 41 48 | 83 86\n").is_ok());
+        assert_eq!(parse("Card 1: 41 48 | 83 86\n\n").err().unwrap().line, 2);
     }
 }
\ No newline at end of file
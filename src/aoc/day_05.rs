@@ -1,3 +1,9 @@
+use crate::cancel;
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+
 #[derive(Debug)]
 struct Range {
     src_start: i64,
@@ -6,6 +12,7 @@ struct Range {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)] // the names are only shown when debugging an almanac
 struct Map {
     src: String,
     dst: String,
@@ -31,7 +38,7 @@ impl Map {
     }
 }
 
-fn find_location(seed: i64, mapping: &Vec<Map>) -> i64 {
+fn find_location(seed: i64, mapping: &[Map]) -> i64 {
     let mut location = seed;
     for map in mapping {
         location = map.map(location);
@@ -40,79 +47,142 @@ fn find_location(seed: i64, mapping: &Vec<Map>) -> i64 {
     return location;
 }
 
-pub fn solve_part_1(almanac: &str) -> i64 {
-    let seeds = read_seeds(almanac);
-    let mapping: Vec<Map> = build_mapping(almanac);
-    seeds.iter().map(|&seed| find_location(seed, &mapping)).min().unwrap()
+/// The seeds to plant and the maps from seeds to their locations.
+pub struct Almanac {
+    seeds: Vec<i64>,
+    mapping: Vec<Map>,
 }
 
-fn read_seeds(almanac: &str) -> Vec<i64> {
-    almanac.lines().into_iter()
-        .nth(0).unwrap()
-        .strip_prefix("seeds: ").unwrap()
-        .split_whitespace()
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>()
+/// Parse the seeds and the maps of an almanac.
+pub fn parse(almanac: &str) -> Result<Almanac, ParseError> {
+    let mut sections = parse::sections(almanac);
+    let seeds = sections.next().unwrap_or(almanac);
+    let seeds = parse::field(almanac, seeds, "seeds:")?;
+    let numbers = parse::numbers(almanac, seeds)?;
+    // part two reads the seeds as pairs of a start and a count
+    if numbers.len() % 2 != 0 {
+        return Err(ParseError::at(almanac, &seeds[seeds.len()..], "the count of the last seed range")
+            .found("the end of the line"));
+    }
+    Ok(Almanac {
+        seeds: numbers,
+        mapping: sections.map(|section| build_map(almanac, section)).collect::<Result<_, _>>()?,
+    })
 }
 
-fn build_mapping(almanac: &str) -> Vec<Map> {
-    let mut mapping: Vec<Map> = Vec::new();
-
-    let mut current_map = &mut Map {
-        src: "".to_string(),
-        dst: "".to_string(),
-        ranges: Vec::new(),
-    };
-    for line in almanac.lines().into_iter().skip(1) {
-        if line.is_empty() {
-            continue;
-        }
+pub fn solve_part_1(almanac: &Almanac) -> Result<i64, Error> {
+    almanac.seeds.iter()
+        .map(|&seed| find_location(seed, &almanac.mapping))
+        .min()
+        .ok_or(Error::Unsolvable("no seeds in the almanac".to_string()))
+}
 
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        if parts.len() == 2 {
-            let names = parts[0].split_once("-to-").unwrap();
-            mapping.push(Map {
-                src: names.0.to_string(),
-                dst: names.1.to_string(),
-                ranges: Vec::new(),
-            });
-            current_map = mapping.last_mut().unwrap()
-        } else {
-            // the almanac ranges are defined in the order dst -> src -> length
-            let dst_start = parts[0].parse::<i64>().unwrap();
-            let src_start = parts[1].parse::<i64>().unwrap();
-            let length = parts[2].parse::<i64>().unwrap();
-
-            current_map.ranges.push(Range {
-                src_start,
-                dst_start,
-                length,
-            });
+/// Build a map from its section of the almanac: a `<source>-to-<destination> map:` header and a
+/// range per line.
+fn build_map(almanac: &str, section: &str) -> Result<Map, ParseError> {
+    let (header, ranges) = section.split_once('\n').unwrap_or((section, parse::end(section)));
+    let (src, dst) = header.strip_suffix(" map:")
+        .and_then(|names| names.split_once("-to-"))
+        .ok_or_else(|| ParseError::at(almanac, header, "\"<source>-to-<destination> map:\""))?;
+    let ranges = parse::lines(ranges, |line| {
+        // the almanac ranges are defined in the order dst -> src -> length
+        let numbers = parse::numbers(almanac, line)?;
+        let [dst_start, src_start, length] = numbers[..] else {
+            return Err(ParseError::at(almanac, line, "3 numbers in range line").found(numbers.len().to_string()));
+        };
+        Ok(Range {
+            src_start,
+            dst_start,
+            length,
+        })
+    })?;
+
+    Ok(Map {
+        src: src.to_string(),
+        dst: dst.to_string(),
+        ranges,
+    })
+}
+
+/// Walks every seed of the ranges, so it checks for cancellation as it goes.
+pub fn solve_part_2(almanac: &Almanac) -> Result<i64, Error> {
+    let mut lowest = None;
+    for range in almanac.seeds.chunks_exact(2) {
+        let (seed, count) = (range[0], range[1]);
+        let end = seed.checked_add(count)
+            .ok_or_else(|| Error::Unsolvable(format!("the seed range {} {} ends past the last i64", seed, count)))?;
+        for s in seed..end {
+            cancel::check()?;
+            let location = find_location(s, &almanac.mapping);
+            lowest = Some(lowest.map_or(location, |lowest: i64| lowest.min(location)));
         }
     }
-
-    return mapping;
+    lowest.ok_or(Error::Unsolvable("no seeds in the almanac".to_string()))
 }
 
-pub fn solve_part_2(almanac: &str) -> i64 {
-    let seeds: Vec<i64> = read_seeds(almanac);
-    let seeds: Vec<&[i64]> = seeds.chunks_exact(2).collect();
-
-    let mapping: Vec<Map> = build_mapping(almanac);
-
-    seeds.iter()
-        .map(|&seeds|
-            {
-                let seed = seeds[0];
-                let count = seeds[1];
-                (0..count)
-                    .map(|offset| seed + offset)
-                    .map(|s| find_location(s, &mapping))
-                    .collect::<Vec<i64>>()
-            }
-        )
-        .flatten()
-        .min().unwrap()
+/// Example from the puzzle description.
+pub const TEST_ALMANAC: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_ALMANAC, answer: "35" },
+    Example { part: Part::Two, input: TEST_ALMANAC, answer: "46" },
+];
+
+/// Solver for AOC2023 day 5.
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input = Almanac;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input)?.into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input)?.into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
 #[cfg(test)]
@@ -149,4 +219,34 @@ mod tests {
         assert_eq!(map.map(55), 57);
         assert_eq!(map.map(13), 13);
     }
+
+    #[test]
+    fn test_parts() {
+        let almanac = parse(TEST_ALMANAC).unwrap();
+        assert_eq!(solve_part_1(&almanac).unwrap(), 35);
+        assert_eq!(solve_part_2(&almanac).unwrap(), 46);
+        assert!(matches!(solve_part_1(&parse("seeds:").unwrap()), Err(Error::Unsolvable(_))));
+    }
+
+    #[test]
+    fn test_seed_range_too_long() {
+        let almanac = parse("seeds: 9223372036854775800 10").unwrap();
+        assert_eq!(solve_part_1(&almanac).unwrap(), 10);
+        assert!(matches!(solve_part_2(&almanac), Err(Error::Unsolvable(_))));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("seeds: 79 14\n\nseed-to-soil map:\n50 98\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (4, 1, "50 98"));
+        assert_eq!(error.to_string(), "line 4, column 1: expected 3 numbers in range line, found 2");
+
+        let error = parse("seeds: 79 14\n\n50 98 2").err().unwrap();
+        assert_eq!(error.expected, "\"<source>-to-<destination> map:\"");
+        assert_eq!(parse("seed: 79").err().unwrap().token, "seed: 79");
+
+        let error = parse("seeds: 79 14 55\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 16, ""));
+        assert_eq!(error.expected, "the count of the last seed range");
+    }
 }
@@ -1,11 +1,18 @@
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+
 /// Find the lowest time needed to beat the max distance.
 fn find_lowest_winning_time(race_time: i64, max_distance: i64) -> i64 {
     let mut start = 0;
     let mut end = race_time / 2;
     while start < end {
         let time = start + (end - start) / 2;
-        let distance = time * (race_time - time);
-        if distance > max_distance {
+        // a distance too far for an `i64` beats any record
+        let beats_record = time.checked_mul(race_time - time)
+            .is_none_or(|distance| distance > max_distance);
+        if beats_record {
             end = time;
         } else {
             start = time + 1;
@@ -22,36 +29,98 @@ fn ways_to_win(time_ms: i64, max_distance: i64) -> i64 {
     ways + 1
 }
 
-pub fn solve_part_1(input: &str) -> i64 {
-    let rows = input.lines().collect::<Vec<&str>>();
-    let times = rows[0].strip_prefix("Time:").unwrap();
-    let distances = rows[1].strip_prefix("Distance:").unwrap();
+/// The time and the record distance of each race, and of the single race of part two.
+pub struct Races {
+    times: Vec<i64>,
+    distances: Vec<i64>,
+    // the rows read as single numbers
+    time: i64,
+    distance: i64,
+}
 
-    let times: Vec<i64> = times.split_whitespace().filter_map(|n| n.parse::<i64>().ok())
-        .collect();
-    let distances: Vec<i64> = distances.split_whitespace().filter_map(|n| n.parse::<i64>().ok())
-        .collect();
+/// Parse the times and record distances of the races.
+pub fn parse(input: &str) -> Result<Races, ParseError> {
+    let mut rows = input.lines();
+    let times_row = rows.next().unwrap_or(input);
+    let times_field = parse::field(input, times_row, "Time:")?;
+    let times = row_numbers(input, times_field)?;
+    let distances_row = rows.next().unwrap_or(parse::end(input));
+    let distances_field = parse::field(input, distances_row, "Distance:")?;
+    let distances = row_numbers(input, distances_field)?;
+    if distances.len() != times.len() {
+        return Err(ParseError::at(input, distances_row, format!("{} distances, one per race", times.len())));
+    }
+
+    Ok(Races {
+        time: kern(input, times_field)?,
+        distance: kern(input, distances_field)?,
+        times,
+        distances,
+    })
+}
+
+/// The numbers of a row, which can't be negative as times and distances.
+fn row_numbers(input: &str, row: &str) -> Result<Vec<i64>, ParseError> {
+    row.split_whitespace()
+        .map(|token| match parse::number::<i64>(input, token)? {
+            n if n < 0 => Err(ParseError::at(input, token, "a number of at least 0")),
+            n => Ok(n),
+        })
+        .collect()
+}
+
+/// Read the numbers of a row as a single one, ignoring the spaces between them. Fails at the row
+/// if that number doesn't fit in an `i64`.
+fn kern(input: &str, row: &str) -> Result<i64, ParseError> {
+    // the numbers are checked already, only a `+` sign can be left between their digits
+    let digits: String = row.split_whitespace().map(|token| token.trim_start_matches('+')).collect();
+    digits.parse::<i64>()
+        .map_err(|_| ParseError::at(input, row.trim(), "numbers that fit in 64 bits together").found("a bigger number"))
+}
 
-    times.iter().zip(distances.iter())
-        .map(|(&t, &d)| ways_to_win(t, d))
+/// The product can outgrow an `i64` on long races, so it's taken in an `i128`.
+pub fn solve_part_1(races: &Races) -> i128 {
+    races.times.iter().zip(races.distances.iter())
+        .map(|(&t, &d)| ways_to_win(t, d) as i128)
         .product()
 }
 
-pub fn solve_part_2(input: &str) -> i64 {
-    let rows = input.lines().collect::<Vec<&str>>();
-    let times = rows[0].strip_prefix("Time:").unwrap();
-    let distances = rows[1].strip_prefix("Distance:").unwrap();
-
-    let time = times.split_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse::<i64>().unwrap();
-    let distance = distances.split_whitespace()
-        .collect::<Vec<&str>>()
-        .join("")
-        .parse::<i64>().unwrap();
-
-    ways_to_win(time, distance)
+pub fn solve_part_2(races: &Races) -> i64 {
+    ways_to_win(races.time, races.distance)
+}
+
+/// Example from the puzzle description.
+pub const TEST_RACES: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_RACES, answer: "288" },
+    Example { part: Part::Two, input: TEST_RACES, answer: "71503" },
+];
+
+/// Solver for AOC2023 day 6.
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input = Races;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input).into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
 #[cfg(test)]
@@ -67,6 +136,40 @@ mod tests {
         assert_eq!(ways_to_win(71530, 940200), 71503);
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("Time:      7  15   30\nDistance:  9  40").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "3 distances, one per race");
+
+        let error = parse("Time:      7  15   3O\nDistance:  9  40  200").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 20, "3O"));
+        assert_eq!(parse("Time: 7").err().unwrap().expected, "\"Distance:\"");
+    }
+
+    #[test]
+    fn test_kern() {
+        let races = parse(TEST_RACES).unwrap();
+        assert_eq!((races.time, races.distance), (71530, 940200));
+        assert_eq!(kern("7 15 30", "7 15 30"), Ok(71530));
+        // the leading zeros of a number are digits of the kerned one
+        let races = parse("Time:      7  15   30\nDistance:  9 040  200").unwrap();
+        assert_eq!(races.distance, 9040200);
+        assert_eq!(kern("7 +15 30", "7 +15 30"), Ok(71530));
+
+        let input = "Time: 1000000000 1000000000\nDistance: 9 40";
+        let error = parse(input).err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 7, "1000000000 1000000000"));
+        assert_eq!(error.found.as_deref(), Some("a bigger number"));
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        let error = parse("Time: 7 -15\nDistance: 9 40").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 9, "-15"));
+        assert_eq!(error.expected, "a number of at least 0");
+    }
+
     #[test]
     fn test_find_lowest_winning_time() {
         assert_eq!(find_lowest_winning_time(7, 9), 2);
@@ -75,4 +178,11 @@ mod tests {
 
         assert_eq!(find_lowest_winning_time(71530, 940200), 14);
     }
+
+    #[test]
+    fn test_long_races() {
+        assert_eq!(find_lowest_winning_time(9999999999, 1), 1);
+        assert_eq!(solve_part_1(&parse("Time: 9999999999\nDistance: 1").unwrap()), 9999999998);
+        assert_eq!(ways_to_win(i64::MAX, i64::MAX - 1), i64::MAX - 3);
+    }
 }
\ No newline at end of file
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
 use crate::day_07::HandType::{FiveOfAKind, FourOfAKind, FullHouse, HighCard, OnePair, ThreeOfAKind, TwoPair};
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
 
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Copy, Clone)]
 struct Cards([char; 5]);
@@ -166,30 +170,39 @@ fn type_of_hand_j(cards: &Cards) -> HandType {
     }
 }
 
-pub fn solve_part_1(camel_cards: &str) -> i64 {
-    let mut entries: Vec<Entry> = camel_cards.lines().map(|line| {
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        let cards: Cards = parts[0].chars().collect::<Vec<char>>().try_into().unwrap();
-        let hand = Hand::new(cards, false);
-        let bid = parts[1].parse().unwrap();
-        Entry { hand, bid }
-    }).collect();
+/// A hand of cards and its bid, before knowing whether `J` is a jack or a joker.
+pub struct Play {
+    cards: Cards,
+    bid: i64,
+}
 
+/// Parse the hands and bids, a play per line.
+pub fn parse(camel_cards: &str) -> Result<Vec<Play>, ParseError> {
+    parse::lines(camel_cards, |line| {
+        let (hand, bid) = parse::pair(camel_cards, line, " ", "\"<hand> <bid>\"")?;
+        if hand.chars().count() != 5 || hand.chars().any(|card| card_rank(card) == 0) {
+            return Err(ParseError::at(camel_cards, hand, "5 cards out of AKQJT98765432"));
+        }
+        let cards: Cards = hand.chars().collect::<Vec<char>>().try_into().expect("5 cards");
+        let bid = parse::number(camel_cards, bid)?;
+        Ok(Play { cards, bid })
+    })
+}
+
+fn rank_entries(plays: &[Play], j_is_joker: bool) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = plays.iter()
+        .map(|play| Entry { hand: Hand::new(play.cards, j_is_joker), bid: play.bid })
+        .collect();
     entries.sort_by_key(|entry| entry.hand);
-    calc_winnings(&entries)
+    entries
 }
 
-pub fn solve_part_2(camel_cards: &str) -> i64 {
-    let mut entries: Vec<Entry> = camel_cards.lines().map(|line| {
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        let cards: Cards = parts[0].chars().collect::<Vec<char>>().try_into().unwrap();
-        let hand = Hand::new(cards, true);
-        let bid = parts[1].parse().unwrap();
-        Entry { hand, bid }
-    }).collect();
+pub fn solve_part_1(plays: &[Play]) -> i64 {
+    calc_winnings(&rank_entries(plays, false))
+}
 
-    entries.sort_by_key(|entry| entry.hand);
-    calc_winnings(&entries)
+pub fn solve_part_2(plays: &[Play]) -> i64 {
+    calc_winnings(&rank_entries(plays, true))
 }
 
 fn calc_winnings(entries: &[Entry]) -> i64 {
@@ -200,6 +213,43 @@ fn calc_winnings(entries: &[Entry]) -> i64 {
         }).sum::<i64>()
 }
 
+/// Example from the puzzle description.
+pub const CAMEL_CARDS: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: CAMEL_CARDS, answer: "6440" },
+    Example { part: Part::Two, input: CAMEL_CARDS, answer: "5905" },
+];
+
+/// Solver for AOC2023 day 7.
+pub struct Day07;
+
+impl Solver for Day07 {
+    type Input = Vec<Play>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input).into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::day_07::HandType::FourOfAKind;
@@ -239,22 +289,25 @@ mod test {
         assert_eq!(type_of_hand(&cards), ThreeOfAKind);
     }
 
-
-    const CAMEL_CARDS: &str = "\
-32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483";
-
     #[test]
     fn test_part_1() {
-        assert_eq!(solve_part_1(CAMEL_CARDS), 6440);
+        assert_eq!(solve_part_1(&parse(CAMEL_CARDS).unwrap()), 6440);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(solve_part_2(CAMEL_CARDS), 5905);
+        assert_eq!(solve_part_2(&parse(CAMEL_CARDS).unwrap()), 5905);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("32T3K 765\nT55X5 684").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 1, "T55X5"));
+        assert_eq!(error.expected, "5 cards out of AKQJT98765432");
+
+        let error = parse("32T3K 76S").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 7, "76S"));
+        assert_eq!(parse("32T3K").err().unwrap().expected, "\"<hand> <bid>\"");
     }
 
     #[test]
@@ -1,4 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::cancel;
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
 
 struct Node {
     name: String,
@@ -6,47 +12,66 @@ struct Node {
     right: String,
 }
 
-fn build_nodes_map(map: &str) -> HashMap<String, Node> {
-    // build a list of nodes from the input map
-    let nodes: Vec<Node> = map.lines()
-        .skip(1)
-        .filter(|&line| !line.is_empty())
-        .map(|line| {
-            let parts = line.split('=').collect::<Vec<&str>>();
-            let name = parts[0].trim();
-            let left_right = parts[1].trim()
-                .trim_start_matches('(')
-                .trim_end_matches(')')
-                .splitn(2, ", ")
-                .collect::<Vec<&str>>();
-            let (left, right) = (left_right[0], left_right[1]);
-            Node {
-                name: name.to_string(),
-                left: left.to_string(),
-                right: right.to_string(),
-            }
-        })
-        .collect();
-
-    // build the map
+fn build_nodes_map(map: &str, nodes: &str) -> Result<HashMap<String, Node>, ParseError> {
+    // read the names of the nodes and their neighbours from the input map
+    let links: Vec<(&str, &str, &str)> = parse::lines(nodes, |line| {
+        let (name, left_right) = parse::pair(map, line, "=", "\"<node> = (<left>, <right>)\"")?;
+        let left_right = parse::enclosed(map, left_right, "(", ")", "\"(<left>, <right>)\"")?;
+        let (left, right) = parse::pair(map, left_right, ",", "\"<left>, <right>\"")?;
+        Ok((name, left, right))
+    })?;
+
+    // build the map, once all nodes are known to exist
+    let names: HashSet<&str> = links.iter().map(|&(name, _, _)| name).collect();
     let mut result = HashMap::new();
-    for node in nodes {
-        result.insert(node.name.clone(), node);
+    for &(name, left, right) in &links {
+        for neighbour in [left, right] {
+            if !names.contains(neighbour) {
+                return Err(ParseError::at(map, neighbour, "the name of a node"));
+            }
+        }
+        result.insert(name.to_string(), Node {
+            name: name.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        });
     }
 
-    result
+    Ok(result)
 }
 
-pub fn solve_part_1(input: &str) -> i64 {
-    // let map: HashMap<String, Node> = HashMap::new();
-    let directions = input.lines().next().unwrap().chars();
+/// The left/right instructions and the network of nodes to follow them on.
+pub struct Network {
+    directions: Vec<char>,
     // map a node name to a node
-    let map: HashMap<String, Node> = build_nodes_map(input);
+    nodes: HashMap<String, Node>,
+}
+
+/// Parse the instructions and the nodes of the network.
+pub fn parse(input: &str) -> Result<Network, ParseError> {
+    let mut sections = parse::sections(input);
+    let directions = sections.next().unwrap_or(input);
+    if let Some((i, direction)) = directions.char_indices().find(|&(_, c)| c != 'L' && c != 'R') {
+        let token = &directions[i..i + direction.len_utf8()];
+        return Err(ParseError::at(input, token, "L or R"));
+    }
+
+    Ok(Network {
+        directions: directions.chars().collect(),
+        nodes: build_nodes_map(input, sections.next().unwrap_or(parse::end(input)))?,
+    })
+}
+
+/// Fails with [`Error::TimedOut`] if `ZZZ` can't be reached and the solver is given a timeout.
+pub fn solve_part_1(network: &Network) -> Result<i64, Error> {
+    let map = &network.nodes;
 
     let mut steps = 0;
-    let mut next_node = map.get("AAA").unwrap();
-    let mut dir_iter = directions.into_iter().cycle();
+    let mut next_node = map.get("AAA")
+        .ok_or(Error::Unsolvable("there is no node AAA".to_string()))?;
+    let mut dir_iter = network.directions.iter().cycle();
     while &next_node.name != "ZZZ" {
+        cancel::check()?;
         let direction = dir_iter.next().unwrap();
         let next_name = match direction {
             'L' => &next_node.left,
@@ -56,14 +81,13 @@ pub fn solve_part_1(input: &str) -> i64 {
         steps += 1;
     }
 
-    steps
+    Ok(steps)
 }
 
-pub fn solve_part_2(input: &str) -> i64 {
-    // let map: HashMap<String, Node> = HashMap::new();
-    let directions: Vec<char> = input.lines().next().unwrap().chars().collect();
-    // map a node name to a node
-    let map: HashMap<String, Node> = build_nodes_map(input);
+/// Fails with [`Error::TimedOut`] if a ghost can't reach a `..Z` node and the solver is given a
+/// timeout.
+pub fn solve_part_2(network: &Network) -> Result<i128, Error> {
+    let map = &network.nodes;
 
     let start_nodes: Vec<&Node> = map.values()
         .filter(|&node| node.name.ends_with('A'))
@@ -71,9 +95,10 @@ pub fn solve_part_2(input: &str) -> i64 {
     let mut steps: Vec<i64> = vec![0; start_nodes.len()];
 
     for (pos, &node) in start_nodes.iter().enumerate() {
-        let mut dir_iter = directions.iter().cycle();
+        let mut dir_iter = network.directions.iter().cycle();
         let mut next_node = node;
         while !next_node.name.ends_with('Z') {
+            cancel::check()?;
             let direction = dir_iter.next().unwrap();
             let next_name = match direction {
                 'L' => &next_node.left,
@@ -84,10 +109,10 @@ pub fn solve_part_2(input: &str) -> i64 {
         }
     }
 
-    steps.iter().fold(1, |a, &b| lcm(a, b))
+    Ok(steps.iter().fold(1, |a, &b| lcm(a, b as i128)))
 }
 
-fn gcd(a: i64, b: i64) -> i64 {
+fn gcd(a: i128, b: i128) -> i128 {
     if b == 0 {
         a.abs()
     } else {
@@ -95,28 +120,21 @@ fn gcd(a: i64, b: i64) -> i64 {
     }
 }
 
-fn lcm(a: i64, b: i64) -> i64 {
-    (a * b).abs() / gcd(a, b)
+/// Divides before multiplying, so that it only overflows if the result does.
+fn lcm(a: i128, b: i128) -> i128 {
+    (a / gcd(a, b) * b).abs()
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn part_1() {
-        let map = "\
+/// Example from part one of the puzzle description.
+pub const TEST_MAP: &str = "\
 LLR
 
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(solve_part_1(map), 6);
-    }
 
-    #[test]
-    fn part_2() {
-        let map = "\
+/// Example from part two of the puzzle description.
+pub const TEST_GHOST_MAP: &str = "\
 LR
 
 11A = (11B, XXX)
@@ -128,6 +146,65 @@ LR
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
-        assert_eq!(solve_part_2(map), 6);
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_MAP, answer: "6" },
+    Example { part: Part::Two, input: TEST_GHOST_MAP, answer: "6" },
+];
+
+/// Solver for AOC2023 day 8.
+pub struct Day08;
+
+impl Solver for Day08 {
+    type Input = Network;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input)?.into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input)?.into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part_1() {
+        assert_eq!(solve_part_1(&parse(TEST_MAP).unwrap()).unwrap(), 6);
+    }
+
+    #[test]
+    fn part_2() {
+        assert_eq!(solve_part_2(&parse(TEST_GHOST_MAP).unwrap()).unwrap(), 6);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (4, 13, "ZZZ"));
+        assert_eq!(error.expected, "the name of a node");
+
+        let error = parse("LLR\n\nAAA = (BBB BBB)").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (3, 8, "BBB BBB"));
+        assert_eq!(parse("LXR\n\nAAA = (AAA, AAA)").err().unwrap().token, "X");
+    }
+
+    #[test]
+    fn lcm_of_large_cycles() {
+        let a = 3 * 1_000_000_007;
+        let b = 5 * 1_000_000_007;
+        assert_eq!(lcm(a, b), 15 * 1_000_000_007);
+        assert_eq!(lcm(i64::MAX as i128, 2), i64::MAX as i128 * 2);
     }
 }
\ No newline at end of file
@@ -1,3 +1,8 @@
+use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{Error, ParseError};
+use crate::solver::{Example, Part, Solver};
+
 enum Direction {
     Left,
     Right,
@@ -26,21 +31,52 @@ fn find_next_value(seq: &Vec<i64>, direction: Direction) -> i64 {
     outer_most_vals.iter().rev().fold(0, accumulator)
 }
 
-pub fn solve_part_1(input: &str) -> i64 {
-    let history_list: Vec<Vec<i64>> = build_history(input);
+pub fn solve_part_1(history_list: &[Vec<i64>]) -> i64 {
     history_list.iter().map(|history| find_next_value(history, Direction::Right)).sum()
 }
 
-pub fn solve_part_2(input: &str) -> i64 {
-    let history_list: Vec<Vec<i64>> = build_history(input);
+pub fn solve_part_2(history_list: &[Vec<i64>]) -> i64 {
     history_list.iter().map(|history| find_next_value(history, Direction::Left)).sum()
 }
 
-fn build_history(input: &str) -> Vec<Vec<i64>> {
-    input.lines()
-        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
-        .map(|splits| splits.iter().map(|&part| part.parse::<i64>().unwrap()).collect::<Vec<i64>>())
-        .collect()
+/// Parse the history of each value in the report, a history per line.
+pub fn parse(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parse::lines(input, |line| parse::numbers(input, line))
+}
+
+/// Example from the puzzle description.
+pub const TEST_REPORT: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    Example { part: Part::One, input: TEST_REPORT, answer: "114" },
+    Example { part: Part::Two, input: TEST_REPORT, answer: "2" },
+];
+
+/// Solver for AOC2023 day 9.
+pub struct Day09;
+
+impl Solver for Day09 {
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(parse(input)?)
+    }
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_1(input).into()))
+    }
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+        Ok(Some(solve_part_2(input).into()))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
 #[cfg(test)]
@@ -58,4 +94,10 @@ mod test {
     fn test_find_next_value_left() {
         assert_eq!(find_next_value(&vec![10, 13, 16, 21, 30, 45], Direction::Left), 5);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("0 3 6 9 12 15\n1 3 6 1O 15 21").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 7, "1O"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,360 @@
+//! A rectangular grid of cells, for the puzzles whose input is a map or a schematic.
+//!
+//! ```
+//! use aoc2023::aoc::grid::{Grid, Position};
+//!
+//! let grid = Grid::parse("467..\n...*.\n..35.").unwrap();
+//! assert_eq!((grid.width(), grid.height()), (5, 3));
+//! assert_eq!(grid.get(Position::new(3, 1)), Some(&'*'));
+//! assert_eq!(grid.get(Position::new(5, 1)), None);
+//!
+//! // the numbers, and whether a symbol is around them
+//! let numbers: Vec<(String, bool)> = grid.runs(char::is_ascii_digit).iter()
+//!     .map(|digits| {
+//!         let number = digits.iter().map(|&digit| grid[digit]).collect();
+//!         (number, grid.border(digits).iter().any(|&around| grid[around] == '*'))
+//!     })
+//!     .collect();
+//! assert_eq!(numbers, vec![("467".to_string(), true), ("35".to_string(), true)]);
+//! ```
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
+use std::ops::{Index, IndexMut};
+
+use crate::aoc::parse;
+use crate::error::ParseError;
+
+/// A top-left - bottom-right position in a grid.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Position {
+    pub x: usize, // column
+    pub y: usize, // row
+}
+
+impl Position {
+    pub fn new(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+}
+
+/// Offsets of the neighbours up, left, right and down.
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of the neighbours with the diagonals, row by row.
+const NEIGHBOURS_8: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Cells in rows of the same width, stored row by row.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// Parse a grid with a cell per character of a line.
+    pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_with(input, "a cell", Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` by `height` cells, all of them `cell`.
+    pub fn new(width: usize, height: usize, cell: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![cell; width * height] }
+    }
+
+    /// Parse a grid with a cell per character of a line, failing with the `expected` cells at the
+    /// first character that `cell` doesn't turn into one, or at a line that isn't as wide as the
+    /// first.
+    pub fn parse_with(input: &str, expected: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let rows = parse::lines(input, |line| {
+            let row = line.char_indices()
+                .map(|(i, c)| cell(c).ok_or_else(|| ParseError::at(input, &line[i..i + c.len_utf8()], expected)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            let first_width = *width.get_or_insert(row.len());
+            if row.len() != first_width {
+                return Err(ParseError::at(input, line, format!("a row of {} cells", first_width))
+                    .found(format!("{} cells", row.len())));
+            }
+            Ok(row)
+        })?;
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the position is inside the grid.
+    pub fn contains(&self, position: Position) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    /// The cell at the position, if it's inside the grid.
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.offset(position).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.offset(position).map(|i| &mut self.cells[i])
+    }
+
+    fn offset(&self, position: Position) -> Option<usize> {
+        self.contains(position).then_some(position.y * self.width + position.x)
+    }
+
+    /// All positions of the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Position::new(i % width, i / width))
+    }
+
+    /// All cells of the grid with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The rows of the grid, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        // an empty grid has no cells to chunk, whatever the width
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells of a column, top to bottom. A column outside the grid has none.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(height)
+    }
+
+    /// The columns of the grid, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The neighbours of a position up, left, right and down, without those outside the grid.
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(position, &NEIGHBOURS_4)
+    }
+
+    /// The neighbours of a position with the diagonals, row by row, without those outside the
+    /// grid.
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(position, &NEIGHBOURS_8)
+    }
+
+    fn neighbours(&self, position: Position, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Position> + '_ {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let x = position.x.checked_add_signed(dx)?;
+            let y = position.y.checked_add_signed(dy)?;
+            Some(Position::new(x, y)).filter(|&neighbour| self.contains(neighbour))
+        })
+    }
+
+    /// The cells around a group of cells, diagonals included, row by row.
+    pub fn border(&self, cells: &[Position]) -> Vec<Position> {
+        let group: HashSet<Position> = cells.iter().copied().collect();
+        let mut border: Vec<Position> = cells.iter()
+            .flat_map(|&cell| self.neighbours_8(cell))
+            .filter(|neighbour| !group.contains(neighbour))
+            .collect();
+        border.sort_by_key(|position| (position.y, position.x));
+        border.dedup();
+        border
+    }
+
+    /// The runs of cells next to each other in a row that `in_run` accepts, e.g. the numbers of a
+    /// schematic. Row by row, left to right.
+    pub fn runs(&self, in_run: impl Fn(&T) -> bool) -> Vec<Vec<Position>> {
+        let mut runs = Vec::new();
+        for (y, row) in self.rows().enumerate() {
+            let mut run = Vec::new();
+            for (x, cell) in row.iter().enumerate() {
+                if in_run(cell) {
+                    run.push(Position::new(x, y));
+                } else if !run.is_empty() {
+                    runs.push(mem::take(&mut run));
+                }
+            }
+            if !run.is_empty() {
+                runs.push(run);
+            }
+        }
+        runs
+    }
+
+    /// The regions of cells that `in_region` accepts, each connected through the up, left, right
+    /// and down neighbours of its cells. The regions are in the order of their first cell, and
+    /// their cells row by row.
+    pub fn regions(&self, in_region: impl Fn(&T) -> bool) -> Vec<Vec<Position>> {
+        let mut seen = vec![false; self.cells.len()];
+        let mut regions = Vec::new();
+        for (start, cell) in self.iter() {
+            let offset = start.y * self.width + start.x;
+            if seen[offset] || !in_region(cell) {
+                continue;
+            }
+
+            // fill the region from its first cell
+            seen[offset] = true;
+            let mut region = Vec::new();
+            let mut stack = vec![start];
+            while let Some(position) = stack.pop() {
+                region.push(position);
+                for neighbour in self.neighbours_4(position) {
+                    let offset = neighbour.y * self.width + neighbour.x;
+                    if !seen[offset] && in_region(&self.cells[offset]) {
+                        seen[offset] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            region.sort_by_key(|position| (position.y, position.x));
+            regions.push(region);
+        }
+        regions
+    }
+
+    /// A grid of the same size with `f` applied to each cell, e.g. to print it with some cells
+    /// marked.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the position is outside the grid.
+    fn index(&self, position: Position) -> &T {
+        match self.offset(position) {
+            Some(offset) => &self.cells[offset],
+            None => panic!("{:?} is outside the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    /// Panics if the position is outside the grid.
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        match self.offset(position) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!("{:?} is outside the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
+
+/// The cells row by row, a line per row, as the grid was parsed.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            row.iter().try_for_each(|cell| write!(f, "{}", cell))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "\
+#.##
+#..#
+..##";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(MAP).unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.to_string(), MAP);
+        assert_eq!(Grid::parse("").unwrap(), Grid::new(0, 0, ' '));
+
+        let error = Grid::parse("#.##\n#.#\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 1, "a row of 4 cells"));
+        assert_eq!(error.found.as_deref(), Some("3 cells"));
+
+        let error = Grid::parse_with(MAP, "# or .", |c| (c == '#').then_some(1)).err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 2, "."));
+    }
+
+    #[test]
+    fn test_indexing() {
+        let mut grid = Grid::parse(MAP).unwrap();
+        assert_eq!(grid.get(Position::new(3, 2)), Some(&'#'));
+        assert_eq!(grid.get(Position::new(4, 0)), None);
+        assert_eq!(grid.get(Position::new(0, 3)), None);
+        grid[Position::new(1, 0)] = '#';
+        assert_eq!(grid.rows().next(), Some(&['#', '#', '#', '#'][..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 4x3 grid")]
+    fn test_index_outside() {
+        let _ = Grid::parse(MAP).unwrap()[Position::new(4, 0)];
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::parse(MAP).unwrap();
+        assert_eq!(grid.rows().map(|row| row.iter().collect()).collect::<Vec<String>>(), vec!["#.##", "#..#", "..##"]);
+        assert_eq!(grid.columns().map(|column| column.collect()).collect::<Vec<String>>(), vec!["##.", "...", "#.#", "###"]);
+        assert_eq!(grid.column(4).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.neighbours_4(Position::new(0, 0)).collect::<Vec<_>>(),
+                   vec![Position::new(1, 0), Position::new(0, 1)]);
+        assert_eq!(grid.neighbours_8(Position::new(2, 1)).collect::<Vec<_>>(),
+                   vec![Position::new(1, 0), Position::new(2, 0), Position::new(1, 1)]);
+        assert_eq!(grid.neighbours_8(Position::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn test_border() {
+        let grid = Grid::parse(MAP).unwrap();
+        assert_eq!(grid.border(&[Position::new(0, 0), Position::new(1, 0)]),
+                   vec![Position::new(2, 0), Position::new(0, 1), Position::new(1, 1), Position::new(2, 1)]);
+        assert_eq!(grid.border(&[Position::new(3, 2)]),
+                   vec![Position::new(2, 1), Position::new(3, 1), Position::new(2, 2)]);
+        assert_eq!(grid.border(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_runs_and_regions() {
+        let grid = Grid::parse(MAP).unwrap();
+        let at = |positions: &[(usize, usize)]| positions.iter().map(|&(x, y)| Position::new(x, y)).collect::<Vec<_>>();
+        assert_eq!(grid.runs(|&c| c == '#'), vec![
+            at(&[(0, 0)]), at(&[(2, 0), (3, 0)]), at(&[(0, 1)]), at(&[(3, 1)]), at(&[(2, 2), (3, 2)]),
+        ]);
+        assert_eq!(grid.regions(|&c| c == '#'), vec![
+            at(&[(0, 0), (0, 1)]), at(&[(2, 0), (3, 0), (3, 1), (2, 2), (3, 2)]),
+        ]);
+        assert_eq!(grid.regions(|&c| c == '.').len(), 1);
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = Grid::parse(MAP).unwrap().map(|&c| if c == '#' { 1 } else { 0 });
+        assert_eq!(grid.iter().map(|(_, &cell)| cell).sum::<i32>(), 7);
+        assert_eq!(grid.to_string(), "1011\n1001\n0011");
+    }
+}
@@ -0,0 +1,156 @@
+//! Helpers to parse puzzle inputs, failing with a [`ParseError`] at the offending token.
+//!
+//! Each helper takes the whole `input` next to the text it parses, which has to be a slice of the
+//! input, so that errors point at the right line and column:
+//!
+//! ```
+//! use aoc2023::aoc::parse;
+//!
+//! let input = "Game 1: 3 blue, 4 red\nGame 2: 1 green";
+//! let games = parse::lines(input, |line| {
+//!     let (label, cubes) = parse::pair(input, line, ":", "\"Game <n>: <cubes>\"")?;
+//!     let id: u32 = parse::labeled_number(input, label, "Game")?;
+//!     let cubes = parse::list(cubes, ",", |cubes| {
+//!         let (count, color) = parse::pair(input, cubes, " ", "\"<n> <color>\"")?;
+//!         Ok((parse::number::<u32>(input, count)?, color))
+//!     })?;
+//!     Ok((id, cubes))
+//! }).unwrap();
+//! assert_eq!(games, vec![(1, vec![(3, "blue"), (4, "red")]), (2, vec![(1, "green")])]);
+//! ```
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+/// Parse each line of the text, stopping at the first line that fails.
+pub fn lines<'a, T>(text: &'a str, f: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    text.lines().map(f).collect()
+}
+
+/// The sections of the text, separated by blank lines.
+pub fn sections(text: &str) -> impl Iterator<Item = &str> {
+    text.split("\n\n")
+        .map(|section| section.trim_matches('\n'))
+        .filter(|section| !section.is_empty())
+}
+
+/// The empty text at the end of the input, for errors about something missing.
+pub fn end(input: &str) -> &str {
+    &input[input.len()..]
+}
+
+/// A number, e.g. `42` or `-7`.
+pub fn number<T: FromStr>(input: &str, token: &str) -> Result<T, ParseError> {
+    token.parse::<T>().map_err(|_| ParseError::at(input, token, "a number"))
+}
+
+/// Numbers separated by whitespace, e.g. `41 48  83`.
+pub fn numbers<T: FromStr>(input: &str, text: &str) -> Result<Vec<T>, ParseError> {
+    text.split_whitespace().map(|token| number(input, token)).collect()
+}
+
+/// The value of a labeled field, e.g. ` 7 15 30` for `Time: 7 15 30` and the label `Time:`.
+pub fn field<'a>(input: &str, line: &'a str, label: &str) -> Result<&'a str, ParseError> {
+    line.strip_prefix(label)
+        .ok_or_else(|| ParseError::at(input, line, format!("{:?}", label)))
+}
+
+/// The number of a label like `Card 12`, with any spaces between the label and the number.
+pub fn labeled_number<T: FromStr>(input: &str, text: &str, label: &str) -> Result<T, ParseError> {
+    text.strip_prefix(label)
+        .filter(|number| number.starts_with(' '))
+        .and_then(|number| number.trim_start().parse::<T>().ok())
+        .ok_or_else(|| ParseError::at(input, text, format!("\"{} <n>\"", label)))
+}
+
+/// The two sides of the first `separator`, without the spaces around them, e.g. `AAA` and
+/// `(BBB, CCC)` for `AAA = (BBB, CCC)` and `=`. The `expected` shape is reported if there is no
+/// separator.
+pub fn pair<'a>(input: &str, text: &'a str, separator: &str, expected: &str) -> Result<(&'a str, &'a str), ParseError> {
+    let text = text.trim();
+    text.split_once(separator)
+        .map(|(left, right)| (left.trim(), right.trim()))
+        .ok_or_else(|| ParseError::at(input, text, expected))
+}
+
+/// The text between `open` and `close`, e.g. `BBB, CCC` for `(BBB, CCC)`.
+pub fn enclosed<'a>(input: &str, text: &'a str, open: &str, close: &str, expected: &str) -> Result<&'a str, ParseError> {
+    text.strip_prefix(open)
+        .and_then(|text| text.strip_suffix(close))
+        .ok_or_else(|| ParseError::at(input, text, expected))
+}
+
+/// Parse each item of a list, e.g. `3 blue, 4 red` separated by `,`. The items are trimmed.
+pub fn list<'a, T>(text: &'a str, separator: &str, f: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    text.split(separator).map(str::trim).map(f).collect()
+}
+
+/// The index of the token among the choices, e.g. 1 for `green` in `red`, `green` and `blue`.
+pub fn one_of(input: &str, token: &str, choices: &[&str]) -> Result<usize, ParseError> {
+    choices.iter()
+        .position(|&choice| choice == token)
+        .ok_or_else(|| {
+            let expected = match choices {
+                [] => "nothing".to_string(),
+                [choice] => choice.to_string(),
+                [choices @ .., last] => format!("{} or {}", choices.join(", "), last),
+            };
+            ParseError::at(input, token, expected)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\n\n\n\nZZZ = (ZZZ, ZZZ)\n";
+        assert_eq!(sections(input).collect::<Vec<&str>>(),
+                   vec!["LLR", "AAA = (BBB, BBB)\nBBB = (AAA, AAA)", "ZZZ = (ZZZ, ZZZ)"]);
+        assert_eq!(sections("").count(), 0);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let input = "Time:      7  15   30\nDistance:  9  4O  200";
+        let (times, distances) = input.split_once('\n').unwrap();
+        assert_eq!(numbers::<i64>(input, field(input, times, "Time:").unwrap()).unwrap(), vec![7, 15, 30]);
+        let error = numbers::<i64>(input, field(input, distances, "Distance:").unwrap()).err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 15, "4O"));
+        assert_eq!(field(input, distances, "Time:").err().unwrap().expected, "\"Time:\"");
+    }
+
+    #[test]
+    fn test_labeled_number() {
+        let input = "Card   12: 41 | 83";
+        assert_eq!(labeled_number::<u32>(input, &input[..9], "Card"), Ok(12));
+        assert_eq!(labeled_number::<u32>(input, &input[..9], "Game").err().unwrap().expected, "\"Game <n>\"");
+        assert!(labeled_number::<u32>("Card12", "Card12", "Card").is_err());
+    }
+
+    #[test]
+    fn test_pair_and_enclosed() {
+        let input = "AAA = (BBB, CCC)\nDDD (EEE, FFF)";
+        let (first, second) = input.split_once('\n').unwrap();
+        let (name, links) = pair(input, first, "=", "\"<node> = (<left>, <right>)\"").unwrap();
+        assert_eq!((name, links), ("AAA", "(BBB, CCC)"));
+        assert_eq!(pair(input, enclosed(input, links, "(", ")", "").unwrap(), ",", ""), Ok(("BBB", "CCC")));
+
+        let error = pair(input, second, "=", "\"<node> = (<left>, <right>)\"").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 1, "DDD (EEE, FFF)"));
+        assert_eq!(enclosed(input, name, "(", ")", "\"(<left>, <right>)\"").err().unwrap().token, "AAA");
+    }
+
+    #[test]
+    fn test_list_and_one_of() {
+        let input = "3 blue, 4 red, 1 purple";
+        let error = list(input, ",", |cubes| {
+            let (_, color) = pair(input, cubes, " ", "\"<n> <color>\"")?;
+            one_of(input, color, &["red", "green", "blue"])
+        }).err().unwrap();
+        assert_eq!((error.column, error.token.as_str()), (18, "purple"));
+        assert_eq!(error.expected, "red, green or blue");
+        assert_eq!(list(&input[..13], ",", Ok), Ok(vec!["3 blue", "4 red"]));
+    }
+}
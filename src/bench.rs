@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::normalize;
+use crate::solver::{Part, Puzzle};
+
+/// A timed stage of a day: parsing the input or solving one of the parts.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Parse, Stage::Solve(Part::One), Stage::Solve(Part::Two)];
+
+    /// Name of the stage in a baseline file.
+    fn key(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Solve(Part::One) => "part1",
+            Stage::Solve(Part::Two) => "part2",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Stage> {
+        Stage::ALL.into_iter().find(|stage| stage.key() == key)
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => f.pad("parse"),
+            Stage::Solve(part) => f.pad(&format!("part {}", part)),
+        }
+    }
+}
+
+/// Summary of the measured run times of a stage.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarize the run times of a stage. There must be at least one sample.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut samples = samples.to_vec();
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        // nearest-rank percentile
+        let p95_rank = (n * 95).div_ceil(100).max(1);
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / n as u32,
+            p95: samples[p95_rank - 1],
+        }
+    }
+}
+
+/// The stats of a stage: `Ok(None)` if it's a part that isn't solved yet, the error if it fails.
+pub type Measurement = Result<Option<Stats>, Error>;
+
+/// Run each stage of a day `warmup` times without measuring, then `runs` times measured.
+///
+/// The parts are solved on an input that is parsed once, so their times don't include parsing.
+/// Fails if the input can't be parsed. The input is normalized once, before any stage.
+///
+/// Each part is solved once first, and only measured if it has an answer.
+pub fn bench(puzzle: &dyn Puzzle, input: &str, warmup: usize, runs: usize) -> Result<Vec<(Stage, Measurement)>, Error> {
+    let (input, _) = normalize::normalize(input);
+    let input = input.as_ref();
+    let parsed = puzzle.parse(input)?;
+
+    let stats = Stage::ALL.iter()
+        .map(|&stage| {
+            if let Stage::Solve(part) = stage {
+                match puzzle.solve(parsed.as_ref(), part) {
+                    Ok(Some(_)) => {}
+                    Ok(None) => return (stage, Ok(None)),
+                    Err(error) => return (stage, Err(error)),
+                }
+            }
+
+            let run_stage = || match stage {
+                Stage::Parse => { let _ = puzzle.parse(input); }
+                Stage::Solve(part) => { let _ = puzzle.solve(parsed.as_ref(), part); }
+            };
+
+            (0..warmup).for_each(|_| run_stage());
+            let samples: Vec<Duration> = (0..runs.max(1))
+                .map(|_| {
+                    let start = Instant::now();
+                    run_stage();
+                    start.elapsed()
+                })
+                .collect();
+
+            (stage, Ok(Some(Stats::from_samples(&samples))))
+        })
+        .collect();
+    Ok(stats)
+}
+
+/// Stats of earlier benchmarks to compare against, per day and stage.
+///
+/// Stored as text, one stage per line: `<day> <stage> <min> <median> <mean> <p95>`, with the
+/// times in nanoseconds.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    stats: BTreeMap<(u8, Stage), Stats>,
+}
+
+impl Baseline {
+    /// Load a baseline file. A missing file is an empty baseline.
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Baseline::parse(contents.as_str())
+                .map_err(|message| io::Error::new(ErrorKind::InvalidData, message)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    fn parse(contents: &str) -> Result<Baseline, String> {
+        let mut baseline = Baseline::default();
+        for (i, line) in contents.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let invalid = || format!("invalid baseline line {}: {}", i + 1, line);
+            if fields.len() != 6 {
+                return Err(invalid());
+            }
+
+            let day = fields[0].parse::<u8>().map_err(|_| invalid())?;
+            let stage = Stage::from_key(fields[1]).ok_or_else(invalid)?;
+            let nanos = fields[2..].iter()
+                .map(|n| n.parse::<u64>().map(Duration::from_nanos))
+                .collect::<Result<Vec<Duration>, _>>()
+                .map_err(|_| invalid())?;
+            let stats = Stats { min: nanos[0], median: nanos[1], mean: nanos[2], p95: nanos[3] };
+            baseline.stats.insert((day, stage), stats);
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&Stats> {
+        self.stats.get(&(day, stage))
+    }
+
+    pub fn insert(&mut self, day: u8, stage: Stage, stats: Stats) {
+        self.stats.insert((day, stage), stats);
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, stage), stats) in &self.stats {
+            writeln!(f, "{} {} {} {} {} {}", day, stage.key(),
+                     stats.min.as_nanos(), stats.median.as_nanos(),
+                     stats.mean.as_nanos(), stats.p95.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+/// Relative change of the median time compared to a baseline, e.g. `0.25` for 25% slower.
+pub fn change(baseline: &Stats, current: &Stats) -> f64 {
+    let before = baseline.median.as_nanos() as f64;
+    if before == 0.0 {
+        return 0.0;
+    }
+    (current.median.as_nanos() as f64 - before) / before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_stats_odd_samples() {
+        let stats = Stats::from_samples(&millis(&[5, 1, 3, 2, 4]));
+        assert_eq!(stats, Stats {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(3),
+            mean: Duration::from_millis(3),
+            p95: Duration::from_millis(5),
+        });
+    }
+
+    #[test]
+    fn test_stats_even_samples() {
+        let samples = millis(&(1..=20).collect::<Vec<u64>>());
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        let stats = Stats::from_samples(&millis(&[1, 2, 3]));
+        baseline.insert(5, Stage::Parse, stats);
+        baseline.insert(4, Stage::Solve(Part::Two), stats);
+
+        let text = baseline.to_string();
+        assert_eq!(text, "\
+4 part2 1000000 2000000 2000000 3000000
+5 parse 1000000 2000000 2000000 3000000
+");
+        assert_eq!(Baseline::parse(text.as_str()), Ok(baseline));
+    }
+
+    #[test]
+    fn test_baseline_invalid_line() {
+        assert_eq!(Baseline::parse("5 parse 1 2 3"), Err("invalid baseline line 1: 5 parse 1 2 3".to_string()));
+        assert_eq!(Baseline::parse("5 part3 1 2 3 4"), Err("invalid baseline line 1: 5 part3 1 2 3 4".to_string()));
+    }
+
+    #[test]
+    fn test_bench_skips_unsolved_parts() {
+        let stats = bench(&crate::day_03::Day03, crate::day_03::TEST_SCHEMATIC, 0, 2).unwrap();
+        let stages: Vec<(Stage, bool)> = stats.iter()
+            .map(|(stage, stats)| (*stage, matches!(stats, Ok(Some(_)))))
+            .collect();
+        assert_eq!(stages, vec![(Stage::Parse, true), (Stage::Solve(Part::One), true), (Stage::Solve(Part::Two), false)]);
+        assert!(matches!(stats[2].1, Ok(None)));
+
+        let stats = bench(&crate::day_05::Day05, "seeds:", 0, 1).unwrap();
+        assert!(matches!(stats[1].1, Err(Error::Unsolvable(_))));
+    }
+
+    #[test]
+    fn test_change() {
+        let before = Stats::from_samples(&millis(&[10]));
+        let after = Stats::from_samples(&millis(&[15]));
+        assert_eq!(change(&before, &after), 0.5);
+        assert!((change(&after, &before) + 1.0 / 3.0).abs() < 1e-9);
+    }
+}
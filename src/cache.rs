@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::answer::Answer;
+use crate::solver::{Part, Puzzle};
+
+/// File with the cached answers, unless another one is given.
+pub const DEFAULT_FILE: &str = ".aoc-cache";
+
+/// Hash of a puzzle input, with FNV-1a so that it's the same for every build, unlike the hashers
+/// of the standard library.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// What an answer is cached for: the input it was found on and the version of the solver.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Key {
+    pub day: u8,
+    pub part: Part,
+    pub input_hash: u64,
+    pub version: String,
+}
+
+impl Key {
+    pub fn new(day: u8, part: Part, puzzle: &dyn Puzzle, input: &str) -> Key {
+        Key { day, part, input_hash: hash(input), version: puzzle.version().to_string() }
+    }
+}
+
+/// The latest answer to each part of each day.
+///
+/// Stored as text, one answer per line: `<day> <part> <input hash> <solver version> <answer>`, with
+/// the hash in hexadecimal. The answer is the rest of the line, so text answers may have spaces.
+#[derive(Debug, Default, PartialEq)]
+pub struct Cache {
+    answers: BTreeMap<(u8, Part), (u64, String, Answer)>,
+}
+
+impl Cache {
+    /// Load a cache file. A missing file is an empty cache.
+    pub fn load(path: &Path) -> io::Result<Cache> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Cache::parse(contents.as_str())
+                .map_err(|message| io::Error::new(ErrorKind::InvalidData, message)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Cache::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    fn parse(contents: &str) -> Result<Cache, String> {
+        let mut cache = Cache::default();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.splitn(5, ' ').collect();
+            let invalid = || format!("invalid cache line {}: {}", i + 1, line);
+            if fields.len() != 5 {
+                return Err(invalid());
+            }
+
+            let day = fields[0].parse::<u8>().map_err(|_| invalid())?;
+            let part = match fields[1] {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return Err(invalid()),
+            };
+            let input_hash = u64::from_str_radix(fields[2], 16).map_err(|_| invalid())?;
+            let answer = Answer::parse(fields[4]);
+            cache.answers.insert((day, part), (input_hash, fields[3].to_string(), answer));
+        }
+
+        Ok(cache)
+    }
+
+    /// The cached answer for the key, if the input and the solver version are the same.
+    pub fn get(&self, key: &Key) -> Option<&Answer> {
+        match self.answers.get(&(key.day, key.part)) {
+            Some((input_hash, version, answer)) if *input_hash == key.input_hash && *version == key.version => {
+                Some(answer)
+            }
+            _ => None,
+        }
+    }
+
+    /// Cache an answer, replacing the one for an earlier input or solver version.
+    pub fn insert(&mut self, key: Key, answer: Answer) {
+        self.answers.insert((key.day, key.part), (key.input_hash, key.version, answer));
+    }
+}
+
+impl fmt::Display for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part), (input_hash, version, answer)) in &self.answers {
+            writeln!(f, "{} {} {:016x} {} {}", day, part, input_hash, version, answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn key(part: Part, input: &str, version: &str) -> Key {
+        Key { day: 7, part, input_hash: hash(input), version: version.to_string() }
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(""), 0xcbf29ce484222325);
+        assert_eq!(hash("a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(hash("32T3K 765"), hash("32T3K 766"));
+    }
+
+    #[test]
+    fn test_get() {
+        let mut cache = Cache::default();
+        cache.insert(key(Part::One, "input", "1"), Answer::Int(6440));
+        assert_eq!(cache.get(&key(Part::One, "input", "1")), Some(&Answer::Int(6440)));
+        assert_eq!(cache.get(&key(Part::One, "other input", "1")), None);
+        assert_eq!(cache.get(&key(Part::One, "input", "2")), None);
+        assert_eq!(cache.get(&key(Part::Two, "input", "1")), None);
+
+        cache.insert(key(Part::One, "other input", "1"), Answer::Int(6592));
+        assert_eq!(cache.get(&key(Part::One, "input", "1")), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join(format!("aoc2023-cache-{}.txt", std::process::id()));
+        let mut cache = Cache::default();
+        cache.insert(key(Part::One, "input", "1"), Answer::Int(6440));
+        cache.insert(key(Part::Two, "input", "2b"), Answer::from("two words"));
+        cache.insert(Key { day: 8, ..key(Part::Two, "input", "1") }, Answer::Big(1 << 70));
+        cache.save(&path).unwrap();
+
+        assert_eq!(Cache::load(&path).unwrap(), cache);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Cache::load(&path).unwrap(), Cache::default());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(Cache::parse("7 1 xyz 1 6440"), Err("invalid cache line 1: 7 1 xyz 1 6440".to_string()));
+        assert_eq!(Cache::parse("7 3 00 1 6440"), Err("invalid cache line 1: 7 3 00 1 6440".to_string()));
+    }
+}
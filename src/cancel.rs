@@ -0,0 +1,94 @@
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+
+/// How many checks pass between looks at the clock, to keep checking cheap in tight loops.
+const CHECK_INTERVAL: u32 = 1024;
+
+/// Tells a solver when to give up. Solvers don't get the token itself: it's installed for the
+/// thread with [`scope`], and long-running loops ask it whether to stop with [`check`].
+#[derive(Clone, Copy, Debug)]
+pub struct Token {
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl Token {
+    /// A token that never cancels.
+    pub fn none() -> Token {
+        Token { deadline: None }
+    }
+
+    /// A token that cancels once `timeout` has passed from now.
+    pub fn with_timeout(timeout: Duration) -> Token {
+        Token { deadline: Some((Instant::now() + timeout, timeout)) }
+    }
+
+    /// Fails with [`Error::TimedOut`] if the deadline has passed.
+    pub fn check(&self) -> Result<(), Error> {
+        match self.deadline {
+            Some((deadline, timeout)) if Instant::now() >= deadline => Err(Error::TimedOut(timeout)),
+            _ => Ok(()),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Token> = const { RefCell::new(Token { deadline: None }) };
+    static CHECKS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Run `f` with `token` installed for the current thread, restoring the previous token afterwards.
+pub fn scope<T>(token: Token, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.replace(token);
+    CHECKS.set(0);
+    let result = f();
+    CURRENT.set(previous);
+    // look at the clock on the next check, the restored token may have expired meanwhile
+    CHECKS.set(0);
+    result
+}
+
+/// Fails with [`Error::TimedOut`] if the token of the current thread has expired. Meant to be
+/// called, with `?`, on every iteration of a loop that may run for long.
+pub fn check() -> Result<(), Error> {
+    let checks = CHECKS.get();
+    CHECKS.set(checks.wrapping_add(1));
+    if !checks.is_multiple_of(CHECK_INTERVAL) {
+        return Ok(());
+    }
+    CURRENT.with_borrow(Token::check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spin() -> Result<(), Error> {
+        loop {
+            check()?;
+        }
+    }
+
+    #[test]
+    fn test_scope_times_out() {
+        let timeout = Duration::from_millis(20);
+        assert!(matches!(scope(Token::with_timeout(timeout), spin), Err(Error::TimedOut(t)) if t == timeout));
+    }
+
+    #[test]
+    fn test_no_deadline() {
+        let result = scope(Token::none(), || (0..10_000).try_for_each(|_| check()));
+        assert!(result.is_ok());
+        assert!(Token::with_timeout(Duration::from_secs(60)).check().is_ok());
+        assert!(Token::with_timeout(Duration::ZERO).check().is_err());
+    }
+
+    #[test]
+    fn test_scope_restores_token() {
+        scope(Token::with_timeout(Duration::ZERO), || {
+            scope(Token::none(), || assert!((0..10_000).try_for_each(|_| check()).is_ok()));
+            assert!(check().is_err());
+        });
+    }
+}
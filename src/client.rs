@@ -0,0 +1,329 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::solver::Part;
+
+/// Where the puzzles are hosted, unless another base URL is given.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable with the session token of the logged-in user.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// File with the session token, used if the environment variable isn't set.
+pub const DEFAULT_SESSION_FILE: &str = ".aoc-session";
+
+const USER_AGENT: &str = "github.com/freespirit/aoc2023";
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// Neither the environment variable nor the session file have a token.
+    MissingSession,
+    /// The server answered with an error status.
+    Status(u16, String),
+    /// The server couldn't be reached or the response couldn't be read.
+    Transport(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => {
+                write!(f, "no session token, set {} or write it to {}", SESSION_ENV, DEFAULT_SESSION_FILE)
+            }
+            ClientError::Status(code, text) => write!(f, "server answered {} {}", code, text),
+            ClientError::Transport(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<ureq::Error> for ClientError {
+    fn from(error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::Status(code, response) => {
+                ClientError::Status(code, response.status_text().to_string())
+            }
+            ureq::Error::Transport(transport) => ClientError::Transport(transport.to_string()),
+        }
+    }
+}
+
+/// Read the session token from the environment, or else from the session file.
+pub fn find_session(session_file: &Path) -> Result<String, ClientError> {
+    let token = match env::var(SESSION_ENV) {
+        Ok(token) => token,
+        Err(_) => fs::read_to_string(session_file).map_err(|_| ClientError::MissingSession)?,
+    };
+
+    match token.trim() {
+        "" => Err(ClientError::MissingSession),
+        token => Ok(token.to_string()),
+    }
+}
+
+/// The server's reply to a submitted answer.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Incorrect,
+    /// Answered too recently, with the wait time if the server told it.
+    RateLimited(Option<String>),
+    /// The part is already solved or not unlocked yet.
+    WrongLevel,
+    /// A reply that isn't recognized, as text.
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Recognize the verdict in the page the server returns for a submitted answer.
+    pub fn parse(page: &str) -> Verdict {
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if page.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if page.contains("You gave an answer too recently") {
+            let wait = page.split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Verdict::RateLimited(wait)
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown(article_text(page))
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "incorrect, too high"),
+            Verdict::TooLow => write!(f, "incorrect, too low"),
+            Verdict::Incorrect => write!(f, "incorrect"),
+            Verdict::RateLimited(Some(wait)) => write!(f, "rate-limited, wait {}", wait),
+            Verdict::RateLimited(None) => write!(f, "rate-limited"),
+            Verdict::WrongLevel => write!(f, "already solved or not unlocked yet"),
+            Verdict::Unknown(text) => write!(f, "unknown reply: {}", text),
+        }
+    }
+}
+
+/// The text of the `<article>` of a page, without the tags.
+fn article_text(page: &str) -> String {
+    let article = page.split_once("<article>")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Client of the puzzle server, for a logged-in user.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Client {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    fn day_url(&self, day: u8) -> String {
+        format!("{}/2023/day/{}", self.base_url, day)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Download the personal input of a day.
+    pub fn fetch_input(&self, day: u8) -> Result<String, ClientError> {
+        let input = self.agent.get(&format!("{}/input", self.day_url(day)))
+            .set("Cookie", &self.cookie())
+            .call()?
+            .into_string()
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(input)
+    }
+
+    /// Download the input of a day into a file, unless the file is already there.
+    ///
+    /// Returns whether the input was downloaded. Failing to save it is an [`Error::Io`], not a
+    /// remote error.
+    pub fn fetch_input_to(&self, day: u8, path: &Path) -> Result<bool, Error> {
+        if path.exists() {
+            return Ok(false);
+        }
+
+        let input = self.fetch_input(day)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(format!("creating {}", dir.display()), e))?;
+        }
+        fs::write(path, input).map_err(|e| Error::io(format!("writing {}", path.display()), e))?;
+        Ok(true)
+    }
+
+    /// Submit the answer to a part of a day.
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Verdict, ClientError> {
+        let level = part.to_string();
+        let page = self.agent.post(&format!("{}/answer", self.day_url(day)))
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", level.as_str()), ("answer", answer)])?
+            .into_string()
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(Verdict::parse(page.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::thread::JoinHandle;
+
+    use super::*;
+
+    /// Serve a single request with a fixed body. The handle returns the request as text.
+    fn stub_server(status: &str, body: &str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                               status, body.len(), body);
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let (base_url, server) = stub_server("200 OK", "Time: 7 15 30\nDistance: 9 40 200\n");
+        let client = Client::new(&base_url, "abc123");
+
+        assert_eq!(client.fetch_input(6).unwrap(), "Time: 7 15 30\nDistance: 9 40 200\n");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/6/input HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc123\r\n"));
+    }
+
+    #[test]
+    fn test_fetch_input_error() {
+        let (base_url, server) = stub_server("400 Bad Request", "Please log in.");
+        let client = Client::new(&base_url, "expired");
+
+        match client.fetch_input(6) {
+            Err(ClientError::Status(400, _)) => {}
+            other => panic!("expected a 400 status, got {:?}", other),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_to_is_cached() {
+        let dir = env::temp_dir().join(format!("aoc2023-client-{}", std::process::id()));
+        let path = dir.join("day_06.txt");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "cached").unwrap();
+
+        // no server is listening, so only a cached input can succeed
+        let client = Client::new("http://127.0.0.1:1", "abc123");
+        assert!(!client.fetch_input_to(6, &path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "cached");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_to_unwritable_path() {
+        let dir = env::temp_dir().join(format!("aoc2023-client-unwritable-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // a file where the inputs directory should be
+        fs::write(dir.join("inputs"), "").unwrap();
+
+        let (base_url, server) = stub_server("200 OK", "Time: 7\nDistance: 9\n");
+        let client = Client::new(&base_url, "abc123");
+        let error = client.fetch_input_to(6, &dir.join("inputs").join("day_06.txt")).err().unwrap();
+        server.join().unwrap();
+        assert!(matches!(error, Error::Io { .. }), "{:?}", error);
+        assert_eq!(error.exit_code(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let page = "<main><article><p>That's the right answer! You are one gold star closer.</p></article></main>";
+        let (base_url, server) = stub_server("200 OK", page);
+        let client = Client::new(&format!("{}/", base_url), "abc123");
+
+        assert_eq!(client.submit(7, Part::Two, "5905").unwrap(), Verdict::Correct);
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2023/day/7/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc123\r\n"));
+        assert!(request.ends_with("level=2&answer=5905"));
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(Verdict::parse("<article><p>That's not the right answer; your answer is too high.</p></article>"),
+                   Verdict::TooHigh);
+        assert_eq!(Verdict::parse("<article><p>That's not the right answer; your answer is too low.</p></article>"),
+                   Verdict::TooLow);
+        assert_eq!(Verdict::parse("<article><p>That's not the right answer. If you're stuck...</p></article>"),
+                   Verdict::Incorrect);
+        assert_eq!(Verdict::parse("<article><p>You gave an answer too recently. You have 42s left to wait.</p></article>"),
+                   Verdict::RateLimited(Some("42s".to_string())));
+        assert_eq!(Verdict::parse("<article><p>You don't seem to be solving the right level.</p></article>"),
+                   Verdict::WrongLevel);
+        assert_eq!(Verdict::parse("<html><article><p>Something <em>else</em>.</p></article></html>"),
+                   Verdict::Unknown("Something else.".to_string()));
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+use crate::client::ClientError;
+
+/// Why solving a puzzle failed.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed, e.g. `reading inputs/day_05.txt`.
+    Io { operation: String, source: io::Error },
+    /// The input doesn't have the expected format.
+    Parse(ParseError),
+    /// The input is well-formed but has no answer.
+    Unsolvable(String),
+    /// The solver gave up after the time limit.
+    TimedOut(Duration),
+    /// There is no solver for the day.
+    UnknownDay(u8),
+    /// The puzzle server couldn't be reached or refused a request.
+    Remote(ClientError),
+}
+
+impl Error {
+    pub fn io(operation: impl fmt::Display, source: io::Error) -> Error {
+        Error::Io { operation: operation.to_string(), source }
+    }
+
+    /// Exit code of the binary for the error. Codes 1 and 2 are left for failed checks and
+    /// invalid arguments.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Io { .. } => 3,
+            Error::Parse(_) => 4,
+            Error::Unsolvable(_) => 5,
+            Error::UnknownDay(_) => 6,
+            Error::Remote(_) => 7,
+            Error::TimedOut(_) => 8,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { operation, .. } => write!(f, "{} failed", operation),
+            Error::Parse(error) => write!(f, "invalid input: {}", error),
+            Error::Unsolvable(message) => write!(f, "unsolvable input: {}", message),
+            Error::UnknownDay(day) => {
+                let available = crate::DAYS.iter()
+                    .map(|(d, _)| d.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "day {} is not available (available days: {})", day, available)
+            }
+            Error::Remote(_) => write!(f, "talking to the puzzle server failed"),
+            Error::TimedOut(timeout) => write!(f, "timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Remote(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        Error::Remote(error)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+/// Where an input stops having the expected format, and what was expected there.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    /// Line of the input, from 1.
+    pub line: usize,
+    /// Column of the line where the token starts, from 1, in characters.
+    pub column: usize,
+    /// The offending text, empty if the line ended too soon.
+    pub token: String,
+    /// What the input should have instead, e.g. `"Card <n>"` or `a number`.
+    pub expected: String,
+    /// What the input has instead, if the token doesn't tell, e.g. how many numbers a line has.
+    pub found: Option<String>,
+    /// The whole line of the input with the token, to show where it is.
+    pub text: String,
+}
+
+impl ParseError {
+    /// An error at `token`, which has to be a slice of `input` so that its position can be found.
+    /// Other tokens are put at the start of the input.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use aoc2023::error::ParseError;
+    /// let input = "Card 1: 41 48 | 83 86\nCard 2: 13 x2 | 61 30";
+    /// let token = &input[input.find("x2").unwrap()..][..2];
+    /// let error = ParseError::at(input, token, "a number");
+    /// assert_eq!((error.line, error.column), (2, 12));
+    /// assert_eq!(error.to_string(), "line 2, column 12: expected a number, found \"x2\"");
+    /// ```
+    pub fn at(input: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        let start = input.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize).checked_sub(start)
+            .filter(|&offset| offset + token.len() <= input.len())
+            .unwrap_or(0);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            token: token.to_string(),
+            expected: expected.into(),
+            found: None,
+            text: input[line_start..line_end].trim_end_matches('\r').to_string(),
+        }
+    }
+
+    /// Describe what was found instead of the token.
+    pub fn found(self, found: impl Into<String>) -> ParseError {
+        ParseError { found: Some(found.into()), ..self }
+    }
+
+    /// The line of the input with a caret under the token, what was expected and what was found,
+    /// like:
+    ///
+    /// ```text
+    ///   |
+    /// 2 | Crad 2: 13 32 | 61 30
+    ///   | ^^^^^^ expected "Card <n>", found "Crad 2"
+    /// ```
+    pub fn diagnostic(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        // keep the tabs before the token, so that the caret lines up with it
+        let indent: String = self.text.chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = self.token.lines().next().map_or(0, |token| token.chars().count()).max(1);
+        format!("{} |\n{} | {}\n{} | {}{} expected {}, found {}\n",
+                gutter, number, self.text, gutter, indent, "^".repeat(width), self.expected, self.describe_found())
+    }
+
+    /// What was found instead of the expected token.
+    fn describe_found(&self) -> String {
+        match &self.found {
+            Some(found) => found.clone(),
+            None if self.token.is_empty() => "the end of the line".to_string(),
+            None => format!("{:?}", self.token),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}, found {}",
+               self.line, self.column, self.expected, self.describe_found())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+    use std::io::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            Error::io("reading", io::Error::from(ErrorKind::NotFound)),
+            Error::Parse(ParseError::at("", "", "a line")),
+            Error::Unsolvable("".to_string()),
+            Error::UnknownDay(26),
+            Error::Remote(ClientError::MissingSession),
+            Error::TimedOut(Duration::from_secs(1)),
+        ];
+        let mut codes: Vec<u8> = errors.iter().map(Error::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|&code| code > 2));
+    }
+
+    #[test]
+    fn test_io_cause() {
+        let error = Error::io("reading inputs/day_05.txt", io::Error::new(ErrorKind::NotFound, "no such file"));
+        assert_eq!(error.to_string(), "reading inputs/day_05.txt failed");
+        assert_eq!(error.source().unwrap().to_string(), "no such file");
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let input = "seeds: 79 14\n\nséed-to-soil map:\n50 98";
+        let end = &input[input.len()..];
+        assert_eq!(ParseError::at(input, end, "3 numbers"),
+                   ParseError {
+                       line: 4,
+                       column: 6,
+                       token: "".to_string(),
+                       expected: "3 numbers".to_string(),
+                       found: None,
+                       text: "50 98".to_string(),
+                   });
+        let name = &input[input.find("soil").unwrap()..][..4];
+        assert_eq!((ParseError::at(input, name, "").line, ParseError::at(input, name, "").column), (3, 9));
+        assert_eq!(ParseError::at(input, "elsewhere", "").line, 1);
+        assert_eq!(Error::Parse(ParseError::at(input, end, "3 numbers")).to_string(),
+                   "invalid input: line 4, column 6: expected 3 numbers, found the end of the line");
+    }
+
+    #[test]
+    fn test_parse_error_diagnostic() {
+        let input = "Card 1: 41 48 | 83 86\r\nCrad 2: 13 32 | 61 30\r\n";
+        let error = ParseError::at(input, &input[23..29], "\"Card <n>:\"");
+        assert_eq!(error.diagnostic(), "  |\n2 | Crad 2: 13 32 | 61 30\n  | ^^^^^^ expected \"Card <n>:\", found \"Crad 2\"\n");
+
+        let input = "seeds: 79\n\ta-to-b map:\n\t50 98";
+        let error = ParseError::at(input, &input[input.len() - 5..], "3 numbers in range line").found("2");
+        assert_eq!(error.to_string(), "line 3, column 2: expected 3 numbers in range line, found 2");
+        assert_eq!(error.diagnostic(), "  |\n3 | \t50 98\n  | \t^^^^^ expected 3 numbers in range line, found 2\n");
+
+        let error = ParseError::at(input, &input[input.len()..], "a number");
+        assert!(error.diagnostic().ends_with("3 | \t50 98\n  | \t     ^ expected a number, found the end of the line\n"));
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(Error::UnknownDay(26).to_string()
+            .starts_with("day 26 is not available (available days: 1, 2, 3,"));
+    }
+}
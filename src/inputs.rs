@@ -0,0 +1,72 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Directory with the puzzle inputs, unless another one is given.
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// Path of the input of a day in an inputs directory: `<dir>/day_NN.txt`.
+///
+/// # Examples:
+///
+/// ```
+/// use std::path::Path;
+/// assert_eq!(aoc2023::inputs::path(Path::new("inputs"), 7), Path::new("inputs/day_07.txt"));
+/// ```
+pub fn path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day_{:02}.txt", day))
+}
+
+/// Where the input of a day is read from.
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Resolve an input argument: `-` is stdin and anything else a file. Without an argument, the
+    /// input is the day's file in the inputs directory.
+    pub fn resolve(arg: Option<&str>, dir: &Path, day: u8) -> Source {
+        match arg {
+            Some("-") => Source::Stdin,
+            Some(file) => Source::File(PathBuf::from(file)),
+            None => Source::File(path(dir, day)),
+        }
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            Source::File(path) => fs::read_to_string(path),
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "stdin"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let dir = Path::new("puzzles");
+        assert_eq!(Source::resolve(Some("-"), dir, 5), Source::Stdin);
+        assert_eq!(Source::resolve(Some("example.txt"), dir, 5), Source::File(PathBuf::from("example.txt")));
+        assert_eq!(Source::resolve(None, dir, 5), Source::File(PathBuf::from("puzzles/day_05.txt")));
+    }
+}
@@ -8,6 +8,76 @@ pub mod aoc {
     pub mod day_07;
     pub mod day_08;
     pub mod day_09;
+    pub mod grid;
+    pub mod parse;
 }
+pub mod answer;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod client;
+pub mod error;
+pub mod inputs;
+pub mod memory;
+pub mod normalize;
+pub mod output;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+pub mod trace;
+pub mod verify;
+pub mod watch;
 
-pub use aoc::{day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09};
\ No newline at end of file
+pub use aoc::{day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09};
+pub use answer::Answer;
+pub use error::{Error, ParseError};
+pub use solver::{Example, Part, Puzzle, Solver};
+
+/// All solved days, keyed and ordered by day number.
+pub static DAYS: &[(u8, &dyn Puzzle)] = &[
+    (1, &day_01::Day01),
+    (2, &day_02::Day02),
+    (3, &day_03::Day03),
+    (4, &day_04::Day04),
+    (5, &day_05::Day05),
+    (6, &day_06::Day06),
+    (7, &day_07::Day07),
+    (8, &day_08::Day08),
+    (9, &day_09::Day09),
+];
+
+/// Find the puzzle solver of a day.
+///
+/// # Examples:
+///
+/// ```
+/// let input = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48";
+/// let puzzle = aoc2023::puzzle(5).unwrap();
+/// let parsed = puzzle.parse(input).unwrap();
+/// assert_eq!(puzzle.solve(parsed.as_ref(), aoc2023::Part::One).unwrap(), Some(aoc2023::Answer::Int(13)));
+/// ```
+///
+/// ```
+/// assert!(aoc2023::puzzle(26).is_none());
+/// ```
+pub fn puzzle(day: u8) -> Option<&'static dyn Puzzle> {
+    DAYS.iter()
+        .find(|(d, _)| *d == day)
+        .map(|&(_, puzzle)| puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples_have_expected_answers() {
+        for &(day, puzzle) in DAYS {
+            for example in puzzle.examples() {
+                let input = puzzle.parse(example.input).unwrap();
+                assert_eq!(puzzle.solve(input.as_ref(), example.part).unwrap(), Some(Answer::parse(example.answer)),
+                           "day {} part {}", day, example.part);
+            }
+        }
+    }
+}
@@ -1,21 +1,893 @@
+use std::error::Error as _;
 use std::fs;
-use clap::Parser;
-use aoc2023::{day_03, day_04, day_05, day_06, day_07, day_08, day_09};
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+use clap::{Parser, Subcommand, ValueEnum};
+use aoc2023::{Answer, bench, cache, client, DAYS, Error, inputs, output, Part, Puzzle, runner, scaffold, verify, watch};
+use aoc2023::bench::Baseline;
+use aoc2023::cache::{Cache, Key};
+use aoc2023::client::Client;
+use aoc2023::inputs::Source;
+use aoc2023::normalize::Strictness;
+use aoc2023::output::Record;
+use aoc2023::runner::{Options, Report};
+use aoc2023::verify::{Answers, Status};
+use aoc2023::watch::Watcher;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: aoc2023::memory::Counting = aoc2023::memory::Counting;
+
+/// Which part(s) of the puzzle to solve.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum PartArg {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    All,
+}
+
+impl PartArg {
+    fn parts(self) -> Vec<Part> {
+        match self {
+            PartArg::One => vec![Part::One],
+            PartArg::Two => vec![Part::Two],
+            PartArg::All => Part::ALL.to_vec(),
+        }
+    }
+}
+
+impl From<Part> for PartArg {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => PartArg::One,
+            Part::Two => PartArg::Two,
+        }
+    }
+}
+
+/// How to print the answers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// How strict to be about CRLF line endings, trailing whitespace and such in the input, which are
+/// fixed silently without `--strict`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum StrictArg {
+    /// Fix them, but print a warning for each kind.
+    Warn,
+    /// Fail at the first of them.
+    Error,
+}
+
+/// The strictness of `--strict`, if given.
+fn strictness(strict: Option<StrictArg>) -> Strictness {
+    match strict {
+        None => Strictness::Lenient,
+        Some(StrictArg::Warn) => Strictness::Warn,
+        Some(StrictArg::Error) => Strictness::Error,
+    }
+}
 
 /// Solve a daily puzzle from Advent of Code 2023.
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    /// File with input text for the day.
-    input_file: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Day of the puzzle to solve.
+    #[arg(short, long, value_parser = parse_day, required = true)]
+    day: Option<u8>,
+
+    /// Part of the puzzle to solve.
+    #[arg(short, long, value_enum, default_value_t = PartArg::All)]
+    part: PartArg,
+
+    /// File with input text for the day, or `-` to read it from stdin. Defaults to the day's file
+    /// in the inputs directory.
+    input_file: Option<String>,
+
+    /// Directory with the inputs of the days, named `day_NN.txt`.
+    #[arg(long, default_value = inputs::DEFAULT_DIR)]
+    inputs: PathBuf,
+
+    /// Output format of the answers.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Solve again whenever the input file changes, showing how the answers changed.
+    #[arg(long, conflicts_with = "format")]
+    watch: bool,
+
+    /// Solve the examples of the puzzle description instead of an input, and check their answers.
+    #[arg(long, conflicts_with_all = ["input_file", "watch", "format"])]
+    example: bool,
+
+    /// How often to check the input file for changes when watching, in milliseconds.
+    #[arg(long, default_value_t = 500, requires = "watch")]
+    poll_ms: u64,
+
+    /// Give up on a part after this many seconds, reporting it as timed out.
+    #[arg(long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Print how the solvers got to their answers, step by step, before the answers.
+    #[arg(long, conflicts_with_all = ["watch", "format"])]
+    explain: bool,
+
+    /// Report CRLF line endings, a byte order mark, trailing whitespace and trailing blank lines in
+    /// the input instead of fixing them silently: as an error, or as warnings with `--strict=warn`.
+    #[arg(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "error")]
+    strict: Option<StrictArg>,
+
+    #[command(flatten)]
+    cache: CacheArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Solve every day on its input and print a summary table.
+    All {
+        /// Directory with the inputs of the days, named `day_NN.txt`.
+        #[arg(long, default_value = inputs::DEFAULT_DIR)]
+        inputs: PathBuf,
+
+        /// Output format of the answers.
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// Number of days to solve at once, each on its own thread.
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+
+        /// Give up on a part after this many seconds, reporting it as timed out.
+        #[arg(long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+
+        /// Report CRLF line endings, a byte order mark, trailing whitespace and trailing blank lines in
+        /// the input instead of fixing them silently: as an error, or as warnings with `--strict=warn`.
+        #[arg(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "error")]
+        strict: Option<StrictArg>,
+
+        #[command(flatten)]
+        cache: CacheArgs,
+    },
+    /// Time the parse and solve stages of a day over many runs.
+    Bench {
+        /// Day of the puzzle to benchmark.
+        #[arg(short, long, value_parser = parse_day)]
+        day: u8,
+
+        /// Directory with the inputs of the days, named `day_NN.txt`.
+        #[arg(long, default_value = inputs::DEFAULT_DIR)]
+        inputs: PathBuf,
+
+        /// Unmeasured runs of each stage before measuring.
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+
+        /// Measured runs of each stage.
+        #[arg(long, default_value_t = 20)]
+        runs: usize,
+
+        /// File with baseline stats to compare against.
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Store the stats of this run in the baseline file.
+        #[arg(long, requires = "baseline")]
+        save: bool,
+
+        /// Slowdown of the median time, in percent, that counts as a regression.
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Check the answers of every day against the expected ones.
+    Verify {
+        /// File with the expected answers, one `<day> <part> <answer>` per line.
+        #[arg(long, default_value = verify::DEFAULT_ANSWERS)]
+        answers: PathBuf,
+
+        /// Directory with the inputs of the days, named `day_NN.txt`.
+        #[arg(long, default_value = inputs::DEFAULT_DIR)]
+        inputs: PathBuf,
+
+        /// Give up on a part after this many seconds, reporting it as timed out.
+        #[arg(long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+
+        /// Report CRLF line endings, a byte order mark, trailing whitespace and trailing blank lines in
+        /// the input instead of fixing them silently: as an error, or as warnings with `--strict=warn`.
+        #[arg(long, value_enum, num_args = 0..=1, require_equals = true, default_missing_value = "error")]
+        strict: Option<StrictArg>,
+    },
+    /// Download the personal input of a day into the inputs directory, unless it's there already.
+    Fetch {
+        /// Day of the puzzle to download the input of.
+        #[arg(short, long, value_parser = parse_day)]
+        day: u8,
+
+        /// Directory with the inputs of the days, named `day_NN.txt`.
+        #[arg(long, default_value = inputs::DEFAULT_DIR)]
+        inputs: PathBuf,
+
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// Submit the answer to a part of a day.
+    Submit {
+        /// Day of the puzzle to submit the answer of.
+        #[arg(short, long, value_parser = parse_day)]
+        day: u8,
+
+        /// Part of the puzzle to submit the answer of.
+        #[arg(short, long, value_parser = parse_part)]
+        part: Part,
+
+        /// Answer to submit. Defaults to solving the part on the day's input.
+        #[arg(long)]
+        answer: Option<String>,
+
+        /// Directory with the inputs of the days, named `day_NN.txt`.
+        #[arg(long, default_value = inputs::DEFAULT_DIR)]
+        inputs: PathBuf,
+
+        #[command(flatten)]
+        server: ServerArgs,
+    },
+    /// Create the module of a new day and register it.
+    New {
+        /// Day of the new puzzle.
+        #[arg(short, long, value_parser = parse_day)]
+        day: u8,
+
+        /// Root directory of the crate.
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+}
+
+/// How to reach the puzzle server.
+#[derive(clap::Args, Debug)]
+struct ServerArgs {
+    /// Base URL of the puzzle server.
+    #[arg(long, default_value = client::DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// File with the session token, used if the AOC_SESSION environment variable isn't set.
+    #[arg(long, default_value = client::DEFAULT_SESSION_FILE)]
+    session_file: PathBuf,
+}
+
+impl ServerArgs {
+    fn client(&self) -> Result<Client, client::ClientError> {
+        let session = client::find_session(&self.session_file)?;
+        Ok(Client::new(&self.base_url, &session))
+    }
 }
 
-fn main() {
-    let args = Args::parse();
-    match fs::read_to_string(args.input_file) {
-        Ok(schematic) => {
-            println!("Part 1: {}", day_09::solve_part_1(schematic.as_str()));
-            println!("Part 2: {}", day_09::solve_part_2(schematic.as_str()));
+/// Where to keep the answers between runs.
+#[derive(clap::Args, Debug)]
+struct CacheArgs {
+    /// Solve the parts even if their answers are cached, and warn about the cached answers that
+    /// differ, without writing the cache.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// File with the cached answers.
+    #[arg(long, default_value = cache::DEFAULT_FILE)]
+    cache_file: PathBuf,
+}
+
+impl CacheArgs {
+    /// The cached answers. A cache file that can't be read is ignored with a warning, and replaced
+    /// when the cache is saved.
+    fn load(&self) -> Cache {
+        Cache::load(&self.cache_file).unwrap_or_else(|error| {
+            eprintln!("Warning: ignoring the cache in {}: {}", self.cache_file.display(), error);
+            Cache::default()
+        })
+    }
+
+    /// Save the cache, unless `--no-cache` only checks the cached answers.
+    fn save(&self, cache: &Cache) -> Result<(), Error> {
+        if self.no_cache {
+            return Ok(());
+        }
+        cache.save(&self.cache_file).map_err(|e| Error::io(format!("writing {}", self.cache_file.display()), e))
+    }
+}
+
+/// Accept only days of the calendar. Whether a day has a solver is checked when solving it.
+fn parse_day(value: &str) -> Result<u8, String> {
+    let day = value.parse::<u8>().map_err(|e| e.to_string())?;
+    if (1..=25).contains(&day) {
+        Ok(day)
+    } else {
+        Err("the days go from 1 to 25".to_string())
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err("the part is either 1 or 2".to_string()),
+    }
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds = value.parse::<f64>().map_err(|e| e.to_string())?;
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(timeout) if !timeout.is_zero() => Ok(timeout),
+        _ => Err("the timeout is a positive number of seconds".to_string()),
+    }
+}
+
+fn solve_day(day: u8, part: PartArg, source: &Source, options: Options) -> Result<Vec<Report>, Error> {
+    aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    let input = source.read().map_err(|e| Error::io(format!("reading {}", source), e))?;
+    solve_input(day, part, input.as_str(), options)
+}
+
+fn solve_input(day: u8, part: PartArg, input: &str, options: Options) -> Result<Vec<Report>, Error> {
+    let puzzle = aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    runner::run(day, puzzle, input, &part.parts(), options)
+}
+
+/// Records of the cached answers to the parts of a day, if all of them are cached.
+fn cached_records(cache: &Cache, day: u8, puzzle: &dyn Puzzle, input: &str, parts: &[Part]) -> Option<Vec<Record>> {
+    parts.iter()
+        .map(|&part| Some(Record::cached(day, part, cache.get(&Key::new(day, part, puzzle, input))?.clone())))
+        .collect()
+}
+
+/// Cache the answers of a fresh run, flagging the records whose cached answer was different.
+fn update_cache(cache: &mut Cache, puzzle: &dyn Puzzle, input: &str, records: &mut [Record]) {
+    for record in records {
+        if let (Some(part), Some(answer)) = (record.part, &record.answer) {
+            let key = Key::new(record.day, part, puzzle, input);
+            record.cache_mismatch = cache.get(&key).filter(|&cached| cached != answer).cloned();
+            cache.insert(key, answer.clone());
+        }
+    }
+}
+
+/// Solve a day on its input, unless its answers are cached. Returns the records with the reports
+/// of the fresh run, which are empty if the answers came from the cache.
+fn solve_day_cached(day: u8, part: PartArg, source: &Source, options: Options, cache_args: &CacheArgs) -> Result<(Vec<Record>, Vec<Report>), Error> {
+    let puzzle = aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    let input = source.read().map_err(|e| Error::io(format!("reading {}", source), e))?;
+    let mut cache = cache_args.load();
+    // the cache doesn't keep the explanations, nor what was fixed in the input
+    if !cache_args.no_cache && !options.explain && options.strictness == Strictness::Lenient {
+        if let Some(records) = cached_records(&cache, day, puzzle, &input, &part.parts()) {
+            return Ok((records, Vec::new()));
+        }
+    }
+
+    let reports = runner::run(day, puzzle, &input, &part.parts(), options)?;
+    let mut records: Vec<Record> = reports.iter().map(Record::from).collect();
+    update_cache(&mut cache, puzzle, &input, &mut records);
+    cache_args.save(&cache)?;
+    Ok((records, reports))
+}
+
+/// The first error of the parts, if any of them failed.
+fn first_error(reports: Vec<Report>) -> Option<Error> {
+    reports.into_iter().find_map(|report| report.answer.err())
+}
+
+/// Solve the examples of a day and check their answers. Fails if an answer doesn't match.
+fn solve_examples(day: u8, part: PartArg, options: Options) -> Result<ExitCode, Error> {
+    let puzzle = aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    let parts = part.parts();
+    let examples: Vec<_> = puzzle.examples().iter()
+        .filter(|example| parts.contains(&example.part))
+        .collect();
+    if examples.is_empty() {
+        println!("Day {} has no examples of the selected part", day);
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut failures = 0;
+    for (i, example) in examples.iter().enumerate() {
+        let mut reports = runner::run(day, puzzle, example.input, &[example.part], options)?;
+        warn_anomalies(day, &reports);
+        let report = reports.remove(0);
+        match report.answer {
+            Ok(answer) => {
+                let status = Status::check(Some(example.answer), answer);
+                if let Status::Fail { .. } = status {
+                    failures += 1;
+                }
+                println!("Example {}, part {}: {}", i + 1, example.part, status);
+            }
+            Err(error) => {
+                failures += 1;
+                println!("Example {}, part {}: {}", i + 1, example.part, error);
+            }
+        }
+        report.explanation.iter().for_each(|note| println!("  {}", note));
+    }
+
+    Ok(if failures > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+/// Solve a day again whenever its input file changes, until interrupted.
+fn watch_day(day: u8, part: PartArg, source: &Source, poll_interval: Duration, options: Options) -> Result<ExitCode, Error> {
+    aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    let path = match source {
+        Source::File(path) => path,
+        Source::Stdin => {
+            println!("Can't watch stdin, give an input file instead.");
+            return Ok(ExitCode::FAILURE);
         }
-        Err(_) => { println!("Error reading input file."); }
     };
+
+    println!("Watching {} for changes, press Ctrl-C to stop.", path.display());
+    let mut watcher = Watcher::new(path);
+    let mut previous: Vec<Record> = Vec::new();
+    let mut failing = false;
+    loop {
+        match watcher.poll() {
+            Ok(Some(input)) => {
+                failing = false;
+                let result = solve_input(day, part, input.as_str(), options);
+                let records = match &result {
+                    Ok(reports) => {
+                        warn_anomalies(day, reports);
+                        reports.iter().map(Record::from).collect()
+                    }
+                    Err(error) => vec![Record::failed(day, error.to_string())],
+                };
+                println!("--- day {} ---", day);
+                watch::diff(&previous, &records).iter().for_each(|line| println!("{}", line));
+                if let Err(Error::Parse(error)) = &result {
+                    print!("{}", error.diagnostic());
+                }
+                previous = records;
+            }
+            Ok(None) => {}
+            Err(error) => {
+                // report a missing or unreadable file once, not on every poll
+                if !failing {
+                    println!("Error reading {}: {}", path.display(), error);
+                    failing = true;
+                }
+            }
+        }
+        thread::sleep(poll_interval);
+    }
+}
+
+/// Solve all days on up to `jobs` threads, with a record for each part. Days without an input are
+/// skipped, and so are days with cached answers.
+fn solve_all(inputs_dir: PathBuf, jobs: usize, options: Options, cache_args: &CacheArgs) -> Result<Vec<Record>, Error> {
+    let inputs: Vec<(u8, &dyn Puzzle, io::Result<String>)> = DAYS.iter()
+        .map(|&(day, puzzle)| (day, puzzle, fs::read_to_string(inputs::path(&inputs_dir, day))))
+        .collect();
+    let mut cache = cache_args.load();
+    let cached: Vec<Option<Vec<Record>>> = inputs.iter()
+        .map(|(day, puzzle, input)| {
+            if cache_args.no_cache || options.strictness != Strictness::Lenient {
+                return None;
+            }
+            cached_records(&cache, *day, *puzzle, input.as_deref().ok()?, &Part::ALL)
+        })
+        .collect();
+    let days: Vec<(u8, &dyn Puzzle, &str)> = inputs.iter().zip(&cached)
+        .filter(|(_, cached)| cached.is_none())
+        .filter_map(|((day, puzzle, input), _)| Some((*day, *puzzle, input.as_deref().ok()?)))
+        .collect();
+    let mut results = runner::run_all(&days, jobs, options).into_iter();
+
+    let records = inputs.into_iter().zip(cached)
+        .flat_map(|((day, puzzle, input), cached)| {
+            match (input, cached) {
+                (_, Some(records)) => records,
+                (Ok(input), None) => match results.next().expect("a result for each day to solve") {
+                    Ok(reports) => {
+                        warn_anomalies(day, &reports);
+                        let mut records: Vec<Record> = reports.iter().map(Record::from).collect();
+                        update_cache(&mut cache, puzzle, &input, &mut records);
+                        records
+                    }
+                    Err(error) => vec![Record::failed(day, error.to_string())],
+                },
+                (Err(error), None) if error.kind() == ErrorKind::NotFound => {
+                    vec![Record::failed(day, "skipped".to_string())]
+                }
+                (Err(error), None) => {
+                    let error = Error::io(format!("reading {}", inputs::path(&inputs_dir, day).display()), error);
+                    vec![Record::failed(day, describe(&error))]
+                }
+            }
+        })
+        .collect();
+    cache_args.save(&cache)?;
+    Ok(records)
+}
+
+/// An error with its chain of causes, on one line.
+fn describe(error: &Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        description.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    description
+}
+
+/// Print how the solvers got to the answers of a day, a part at a time.
+fn print_explanations(reports: &[Report]) {
+    for report in reports.iter().filter(|report| !report.explanation.is_empty()) {
+        println!("Part {} explained:", report.part);
+        report.explanation.iter().for_each(|note| println!("  {}", note));
+    }
+}
+
+/// An answer, noting whether it came from the cache or differs from the cached one.
+fn format_answer(record: &Record, answer: &Answer) -> String {
+    match &record.cache_mismatch {
+        _ if record.cached => format!("{} (cached)", answer),
+        Some(cached) => format!("{} (cached {}!)", answer, cached),
+        None => answer.to_string(),
+    }
+}
+
+/// Warn about what was fixed in the input of a day, which the parts of the day share.
+fn warn_anomalies(day: u8, reports: &[Report]) {
+    for anomaly in reports.first().map_or(&[][..], |report| &report.anomalies) {
+        eprintln!("Warning: the input of day {}: {}", day, anomaly);
+    }
+}
+
+/// Warn about the answers that differ from the cached ones.
+fn warn_cache_mismatches(records: &[Record]) {
+    cache_mismatches(records).iter().for_each(|warning| eprintln!("Warning: {}", warning));
+}
+
+/// The answers that differ from the cached ones, described.
+fn cache_mismatches(records: &[Record]) -> Vec<String> {
+    records.iter()
+        .filter_map(|record| match (record.part, &record.answer, &record.cache_mismatch) {
+            (Some(part), Some(answer), Some(cached)) => {
+                Some(format!("day {} part {} is {} now, but the cache had {}", record.day, part, answer, cached))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Print the answers of a day as `Part N: <answer>` lines.
+fn print_answers(records: &[Record]) {
+    for record in records {
+        match (record.part, &record.answer, &record.error) {
+            (Some(part), Some(answer), _) => println!("Part {}: {}", part, format_answer(record, answer)),
+            (Some(part), None, Some(error)) => println!("Part {}: {}", part, error),
+            (_, _, error) => println!("{}", error.as_deref().unwrap_or("no answer")),
+        }
+    }
+}
+
+/// Print the answers of all days as a table, with their memory use if it was counted.
+fn print_table(records: &[Record]) {
+    let with_memory = records.iter().any(|record| record.memory.is_some());
+    print!("{:>3}  {:>4}  {:>20}  {:>10}  {:>10}", "Day", "Part", "Answer", "Parse", "Solve");
+    if with_memory {
+        print!("  {:>10}  {:>10}", "Allocated", "Peak");
+    }
+    println!();
+    for record in records {
+        let answer = match (&record.answer, &record.error) {
+            (Some(answer), _) => format_answer(record, answer),
+            (None, error) => error.clone().unwrap_or_default(),
+        };
+        let (parse_time, solve_time) = match record.cached {
+            true => ("-".to_string(), "-".to_string()),
+            false => (format_duration(record.parse_time), format_duration(record.solve_time)),
+        };
+        match record.part {
+            Some(part) => {
+                print!("{:>3}  {:>4}  {:>20}  {:>10}  {:>10}",
+                       record.day, part, answer, parse_time, solve_time);
+                if let Some(memory) = record.memory {
+                    print!("  {:>10}  {:>10}", format_bytes(memory.allocated), format_bytes(memory.peak));
+                }
+                println!();
+            }
+            None => println!("{:>3}  {:>4}  {:>20}", record.day, "-", answer),
+        }
+    }
+}
+
+fn print_records(records: &[Record], format: Format, print_text: fn(&[Record])) {
+    match format {
+        Format::Text => print_text(records),
+        Format::Json => print!("{}", output::json(records)),
+        Format::Csv => print!("{}", output::csv(records)),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.2}{}", size, UNITS[unit])
+    }
+}
+
+struct BenchOptions {
+    warmup: usize,
+    runs: usize,
+    baseline: Option<PathBuf>,
+    save: bool,
+    threshold: f64,
+}
+
+/// Benchmark a day and compare it to a baseline, if there is one.
+fn bench_day(day: u8, inputs_dir: PathBuf, options: BenchOptions) -> Result<(), Error> {
+    let puzzle = aoc2023::puzzle(day).ok_or(Error::UnknownDay(day))?;
+    let input_path = inputs::path(&inputs_dir, day);
+    let input = fs::read_to_string(&input_path)
+        .map_err(|e| Error::io(format!("reading {}", input_path.display()), e))?;
+    let mut baseline = match options.baseline.as_deref() {
+        Some(path) => {
+            let baseline = Baseline::load(path)
+                .map_err(|e| Error::io(format!("reading baseline {}", path.display()), e))?;
+            Some(baseline)
+        }
+        None => None,
+    };
+
+    println!("Day {}: {} warm-up and {} measured runs per stage", day, options.warmup, options.runs);
+    println!("{:>6}  {:>10}  {:>10}  {:>10}  {:>10}  {:>8}",
+             "Stage", "Min", "Median", "Mean", "P95", "Change");
+    for (stage, stats) in bench::bench(puzzle, input.as_str(), options.warmup, options.runs)? {
+        // only measured stages are shown and kept in the baseline
+        let stats = match stats {
+            Ok(Some(stats)) => stats,
+            Ok(None) => {
+                println!("{:>6}  not solved yet", stage);
+                continue;
+            }
+            Err(error) => {
+                println!("{:>6}  {}", stage, describe(&error));
+                continue;
+            }
+        };
+        let change = match baseline.as_ref().and_then(|b| b.get(day, stage)) {
+            Some(before) => {
+                let change = 100.0 * bench::change(before, &stats);
+                let regression = if change > options.threshold { "  REGRESSION" } else { "" };
+                format!("{:>+7.1}%{}", change, regression)
+            }
+            None => format!("{:>8}", "-"),
+        };
+        println!("{:>6}  {:>10}  {:>10}  {:>10}  {:>10}  {}", stage,
+                 format_duration(stats.min), format_duration(stats.median),
+                 format_duration(stats.mean), format_duration(stats.p95), change);
+
+        if let Some(baseline) = baseline.as_mut() {
+            baseline.insert(day, stage, stats);
+        }
+    }
+
+    if let (true, Some(path), Some(baseline)) = (options.save, &options.baseline, &baseline) {
+        baseline.save(path).map_err(|e| Error::io(format!("saving baseline {}", path.display()), e))?;
+        println!("Saved baseline to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Check the answers of all days. Fails if an answer doesn't match the expected one.
+fn verify_all(answers_file: &Path, inputs_dir: PathBuf, options: Options) -> Result<ExitCode, Error> {
+    let answers = Answers::load(answers_file)
+        .map_err(|e| Error::io(format!("reading {}", answers_file.display()), e))?;
+
+    let mut failures = 0;
+    for &(day, puzzle) in DAYS {
+        let input = match fs::read_to_string(inputs::path(&inputs_dir, day)) {
+            Ok(input) => input,
+            Err(_) => {
+                println!("Day {}: missing input", day);
+                continue;
+            }
+        };
+        let reports = match runner::run(day, puzzle, input.as_str(), &Part::ALL, options) {
+            Ok(reports) => {
+                warn_anomalies(day, &reports);
+                reports
+            }
+            Err(error) => {
+                failures += 1;
+                println!("Day {}: {}", day, error);
+                continue;
+            }
+        };
+        for report in reports {
+            match report.answer {
+                Ok(answer) => {
+                    let status = Status::check(answers.get(day, report.part), answer);
+                    if let Status::Fail { .. } = status {
+                        failures += 1;
+                    }
+                    println!("Day {} part {}: {}", day, report.part, status);
+                }
+                Err(error) => {
+                    failures += 1;
+                    println!("Day {} part {}: {}", day, report.part, error);
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        println!("{} answer(s) did not match", failures);
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn fetch(day: u8, inputs_dir: &Path, server: &ServerArgs) -> Result<(), Error> {
+    let path = inputs::path(inputs_dir, day);
+    // a downloaded input needs no session
+    if path.exists() {
+        println!("The input of day {} is already in {}", day, path.display());
+        return Ok(());
+    }
+    if server.client()?.fetch_input_to(day, &path)? {
+        println!("Downloaded the input of day {} to {}", day, path.display());
+    } else {
+        println!("The input of day {} is already in {}", day, path.display());
+    }
+    Ok(())
+}
+
+fn submit(day: u8, part: Part, answer: Option<String>, inputs_dir: &Path, server: &ServerArgs) -> Result<ExitCode, Error> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let source = Source::resolve(None, inputs_dir, day);
+            let report = solve_day(day, PartArg::from(part), &source, Options::default())?.remove(0);
+            match report.answer? {
+                Some(answer) => answer.to_string(),
+                None => {
+                    println!("Nothing to submit, day {} part {} is not solved yet", day, part);
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+        }
+    };
+
+    println!("Submitting {} for day {} part {}", answer, day, part);
+    let verdict = server.client()?.submit(day, part, answer.as_str())?;
+    println!("{}", verdict);
+    match verdict {
+        client::Verdict::Correct => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+fn new_day(day: u8, root: &Path) -> Result<(), Error> {
+    let path = scaffold::scaffold(root, day).map_err(|e| Error::io(format!("creating day {}", day), e))?;
+    println!("Created {} and registered day {}", path.display(), day);
+    Ok(())
+}
+
+fn run(args: Args) -> Result<ExitCode, Error> {
+    match args.command {
+        Some(Command::All { inputs, format, jobs, timeout, strict, cache }) => {
+            let options = Options { timeout, strictness: strictness(strict), ..Options::default() };
+            let records = solve_all(inputs, jobs as usize, options, &cache)?;
+            warn_cache_mismatches(&records);
+            print_records(&records, format, print_table)
+        }
+        Some(Command::Bench { day, inputs, warmup, runs, baseline, save, threshold }) => {
+            bench_day(day, inputs, BenchOptions { warmup, runs, baseline, save, threshold })?
+        }
+        Some(Command::Verify { answers, inputs, timeout, strict }) => {
+            let options = Options { timeout, strictness: strictness(strict), ..Options::default() };
+            return verify_all(&answers, inputs, options);
+        }
+        Some(Command::Fetch { day, inputs, server }) => fetch(day, &inputs, &server)?,
+        Some(Command::Submit { day, part, answer, inputs, server }) => {
+            return submit(day, part, answer, &inputs, &server);
+        }
+        Some(Command::New { day, root }) => new_day(day, &root)?,
+        None => {
+            let day = args.day.expect("day is required without a command");
+            let options = Options { timeout: args.timeout, explain: args.explain, strictness: strictness(args.strict) };
+            if args.example {
+                return solve_examples(day, args.part, options);
+            }
+            let source = Source::resolve(args.input_file.as_deref(), &args.inputs, day);
+            if args.watch {
+                let poll_interval = Duration::from_millis(args.poll_ms);
+                return watch_day(day, args.part, &source, poll_interval, options);
+            }
+            let (records, reports) = solve_day_cached(day, args.part, &source, options, &args.cache)?;
+            if args.explain {
+                print_explanations(&reports);
+            }
+            warn_anomalies(day, &reports);
+            warn_cache_mismatches(&records);
+            print_records(&records, args.format, print_answers);
+            // the answers show what went wrong, the exit code tells scripts which kind of error
+            if let Some(error) = first_error(reports) {
+                return Ok(ExitCode::from(error.exit_code()));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            let mut source = error.source();
+            while let Some(cause) = source {
+                eprintln!("  caused by: {}", cause);
+                source = cause.source();
+            }
+            if let Error::Parse(error) = &error {
+                eprint!("{}", error.diagnostic());
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use aoc2023::aoc::day_07::{CAMEL_CARDS, Day07};
+
+    #[test]
+    fn no_cache_flags_stale_answers() {
+        let dir = env::temp_dir().join(format!("aoc2023-main-no-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input_file = dir.join("day_07.txt");
+        fs::write(&input_file, CAMEL_CARDS).unwrap();
+        let mut stale = Cache::default();
+        stale.insert(Key::new(7, Part::One, &Day07, CAMEL_CARDS), Answer::Int(1234));
+        let cache_file = dir.join("cache.txt");
+        stale.save(&cache_file).unwrap();
+
+        let cache_args = CacheArgs { no_cache: true, cache_file: cache_file.clone() };
+        let source = Source::File(input_file);
+        let (records, reports) = solve_day_cached(7, PartArg::One, &source, Options::default(), &cache_args).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(cache_mismatches(&records), vec!["day 7 part 1 is 6440 now, but the cache had 1234"]);
+        // the cache is only checked, not written
+        assert_eq!(Cache::load(&cache_file).unwrap(), stale);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,160 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A global allocator that counts the bytes allocated on each thread, to report the memory use of
+/// the solvers. Install it in a binary to enable [`measure`]:
+///
+/// ```
+/// #[global_allocator]
+/// static ALLOCATOR: aoc2023::memory::Counting = aoc2023::memory::Counting;
+///
+/// fn main() {
+///     let (_, usage, _) = aoc2023::memory::measure(|| vec![0u8; 100]);
+///     assert!(usage.unwrap().allocated >= 100);
+/// }
+/// ```
+pub struct Counting;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // const-initialized cells don't allocate, so the allocator can use them
+    static ALLOCATED: Cell<u64> = const { Cell::new(0) };
+    static LIVE: Cell<i64> = const { Cell::new(0) };
+    static PEAK: Cell<i64> = const { Cell::new(0) };
+}
+
+impl Counting {
+    fn record_alloc(size: usize) {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+        // the thread-locals may be gone already while a thread shuts down, then don't count
+        let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get() + size as u64));
+        let _ = LIVE.try_with(|live| {
+            live.set(live.get() + size as i64);
+            let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+        });
+    }
+
+    fn record_dealloc(size: usize) {
+        let _ = LIVE.try_with(|live| live.set(live.get() - size as i64));
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Counting::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Counting::record_dealloc(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Counting::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Counting::record_dealloc(layout.size());
+            Counting::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Memory used while running some code.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Usage {
+    /// Bytes allocated in total, including the ones freed again.
+    pub allocated: u64,
+    /// Most bytes that were allocated at once, on top of what was live before.
+    pub peak: u64,
+}
+
+impl Usage {
+    /// Usage of two stages that ran one after the other, the second one keeping `retained` bytes
+    /// of the first alive.
+    pub fn then(self, next: Usage, retained: u64) -> Usage {
+        Usage {
+            allocated: self.allocated + next.allocated,
+            peak: self.peak.max(retained + next.peak),
+        }
+    }
+}
+
+/// Whether the [`Counting`] allocator is installed, so that [`measure`] can count.
+pub fn enabled() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Run `f` and count the memory it allocates on the current thread. The usage is `None` unless
+/// the [`Counting`] allocator is installed. Also returns the bytes `f` left allocated, e.g. in its
+/// result.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>, u64) {
+    if !enabled() {
+        return (f(), None, 0);
+    }
+
+    let allocated_before = ALLOCATED.get();
+    let live_before = LIVE.get();
+    let peak_before = PEAK.replace(live_before);
+    let result = f();
+    let usage = Usage {
+        allocated: ALLOCATED.get() - allocated_before,
+        peak: (PEAK.get() - live_before).max(0) as u64,
+    };
+    let retained = (LIVE.get() - live_before).max(0) as u64;
+    // keep the peak of an enclosing measurement
+    PEAK.set(PEAK.get().max(peak_before));
+    (result, Some(usage), retained)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_measure() {
+        let (sum, usage, retained) = measure(|| {
+            let temporary = vec![1u64; 1000];
+            temporary.iter().sum::<u64>()
+        });
+        assert_eq!(sum, 1000);
+        let usage = usage.unwrap();
+        assert!(usage.allocated >= 8000);
+        assert!(usage.peak >= 8000);
+        assert_eq!(retained, 0);
+    }
+
+    #[test]
+    fn test_measure_retained() {
+        let (kept, usage, retained) = measure(|| vec![0u8; 4096]);
+        assert_eq!(kept.len(), 4096);
+        assert!(usage.unwrap().allocated >= 4096);
+        assert!(retained >= 4096);
+    }
+
+    #[test]
+    fn test_then() {
+        let parse = Usage { allocated: 100, peak: 80 };
+        let solve = Usage { allocated: 50, peak: 40 };
+        assert_eq!(parse.then(solve, 60), Usage { allocated: 150, peak: 100 });
+        assert_eq!(parse.then(solve, 10), Usage { allocated: 150, peak: 80 });
+    }
+}
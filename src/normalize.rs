@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::error::ParseError;
+
+/// Something in an input that editors add but the parsers don't expect.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Anomaly {
+    /// The input starts with a byte order mark.
+    ByteOrderMark,
+    /// Lines end with `\r\n`: how many of them, and the first one.
+    CarriageReturns { lines: usize, first: usize },
+    /// Lines end with whitespace before their line ending: how many of them, and the first one.
+    TrailingWhitespace { lines: usize, first: usize },
+    /// Blank lines after the last line of the input.
+    TrailingBlankLines { lines: usize },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anomaly::ByteOrderMark => write!(f, "the input starts with a byte order mark"),
+            Anomaly::CarriageReturns { lines, first } => {
+                write!(f, "{} line(s) end with CRLF, the first is line {}", lines, first)
+            }
+            Anomaly::TrailingWhitespace { lines, first } => {
+                write!(f, "{} line(s) end with whitespace, the first is line {}", lines, first)
+            }
+            Anomaly::TrailingBlankLines { lines } => write!(f, "{} blank line(s) at the end", lines),
+        }
+    }
+}
+
+/// How to treat the anomalies of an input.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum Strictness {
+    /// Fix them silently.
+    #[default]
+    Lenient,
+    /// Fix them, but report them.
+    Warn,
+    /// Fail on the first of them.
+    Error,
+}
+
+/// The input without its anomalies, with the anomalies that were fixed. The input is borrowed as
+/// is if it has none.
+///
+/// # Examples:
+///
+/// ```
+/// use aoc2023::normalize::{Anomaly, normalize};
+/// let (input, anomalies) = normalize("\u{feff}32T3K 765\r\nT55J5 684  \r\n\r\n");
+/// assert_eq!(input, "32T3K 765\nT55J5 684\n");
+/// assert_eq!(anomalies, vec![
+///     Anomaly::ByteOrderMark,
+///     Anomaly::CarriageReturns { lines: 3, first: 1 },
+///     Anomaly::TrailingWhitespace { lines: 1, first: 2 },
+///     Anomaly::TrailingBlankLines { lines: 1 },
+/// ]);
+/// ```
+pub fn normalize(input: &str) -> (Cow<'_, str>, Vec<Anomaly>) {
+    let anomalies = anomalies(input);
+    if anomalies.is_empty() {
+        return (Cow::Borrowed(input), anomalies);
+    }
+
+    let text = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut lines: Vec<&str> = text.split('\n').map(|line| split_line(line).0).collect();
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    let mut normalized = lines.join("\n");
+    if text.ends_with('\n') && !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    (Cow::Owned(normalized), anomalies)
+}
+
+/// The anomalies of an input, in the order [`normalize`] reports them.
+pub fn anomalies(input: &str) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    if input.starts_with('\u{feff}') {
+        anomalies.push(Anomaly::ByteOrderMark);
+    }
+
+    let (mut carriage_returns, mut first_carriage_return) = (0, 0);
+    let (mut whitespace, mut first_whitespace) = (0, 0);
+    for (i, line) in input.split('\n').enumerate() {
+        let (_, trailing_whitespace, carriage_return) = split_line(line);
+        if !carriage_return.is_empty() {
+            carriage_returns += 1;
+            if first_carriage_return == 0 {
+                first_carriage_return = i + 1;
+            }
+        }
+        if !trailing_whitespace.is_empty() {
+            whitespace += 1;
+            if first_whitespace == 0 {
+                first_whitespace = i + 1;
+            }
+        }
+    }
+    if carriage_returns > 0 {
+        anomalies.push(Anomaly::CarriageReturns { lines: carriage_returns, first: first_carriage_return });
+    }
+    if whitespace > 0 {
+        anomalies.push(Anomaly::TrailingWhitespace { lines: whitespace, first: first_whitespace });
+    }
+
+    let blank_lines = input.lines().rev().take_while(|line| split_line(line).0.is_empty()).count();
+    if blank_lines > 0 {
+        anomalies.push(Anomaly::TrailingBlankLines { lines: blank_lines });
+    }
+    anomalies
+}
+
+/// Fail at the first anomaly of the input, for [`Strictness::Error`].
+pub fn check(input: &str) -> Result<(), ParseError> {
+    if input.starts_with('\u{feff}') {
+        let bom = &input[..'\u{feff}'.len_utf8()];
+        return Err(ParseError::at(input, bom, "no byte order mark").found("a byte order mark"));
+    }
+
+    for line in input.split('\n') {
+        let (_, whitespace, carriage_return) = split_line(line);
+        if !whitespace.is_empty() {
+            return Err(ParseError::at(input, whitespace, "the end of the line").found("trailing whitespace"));
+        }
+        if !carriage_return.is_empty() {
+            return Err(ParseError::at(input, carriage_return, "the end of the line").found("a carriage return"));
+        }
+    }
+
+    // only line feeds are left after the last line, one of them ends it
+    let tail = &input[input.trim_end().len()..];
+    let blank_lines = match tail.len() {
+        0 => "",
+        _ if tail.len() == input.len() => tail,
+        _ => &tail[1..],
+    };
+    if !blank_lines.is_empty() {
+        let blank_line = &blank_lines[..0];
+        return Err(ParseError::at(input, blank_line, "the end of the input").found("a blank line"));
+    }
+    Ok(())
+}
+
+/// A line split into its content, the whitespace after it and its `\r` ending, if any. This is
+/// what [`normalize`] drops, [`anomalies`] reports and [`check`] rejects.
+fn split_line(line: &str) -> (&str, &str, &str) {
+    let without_carriage_return = line.strip_suffix('\r').unwrap_or(line);
+    let content = without_carriage_return.trim_end();
+    (content, &without_carriage_return[content.len()..], &line[without_carriage_return.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_input_is_borrowed() {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n";
+        assert!(matches!(normalize(input), (Cow::Borrowed(_), anomalies) if anomalies.is_empty()));
+        assert_eq!(check(input), Ok(()));
+        assert_eq!(check(""), Ok(()));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let (input, anomalies) = normalize("Card 1: 41 | 83\n\n\n");
+        assert_eq!(input, "Card 1: 41 | 83\n");
+        assert_eq!(anomalies, vec![Anomaly::TrailingBlankLines { lines: 2 }]);
+
+        let (input, _) = normalize("Time: 7\t\nDistance: 9 ");
+        assert_eq!(input, "Time: 7\nDistance: 9");
+    }
+
+    #[test]
+    fn test_check() {
+        let error = check("\u{feff}LLR").err().unwrap();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (1, 1, "no byte order mark"));
+
+        let error = check("32T3K 765\r\nT55J5 684\r\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 10, "\r"));
+
+        let error = check("32T3K 765\nT55J5 684 \n").err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (2, 10, " "));
+
+        let error = check("0 3 6\n1 3 6\n\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (3, 1, "the end of the input"));
+    }
+
+    #[test]
+    fn test_any_trailing_whitespace() {
+        // a doubled `\r` and a form feed are whitespace before the line ending too
+        let input = "32T3K 765\r\r\nT55J5 684\u{c}\n";
+        let (normalized, anomalies) = normalize(input);
+        assert_eq!(normalized, "32T3K 765\nT55J5 684\n");
+        assert_eq!(anomalies, vec![
+            Anomaly::CarriageReturns { lines: 1, first: 1 },
+            Anomaly::TrailingWhitespace { lines: 2, first: 1 },
+        ]);
+
+        let error = check(input).err().unwrap();
+        assert_eq!((error.line, error.column, error.token.as_str()), (1, 10, "\r"));
+        assert_eq!(error.found.as_deref(), Some("trailing whitespace"));
+    }
+
+    #[test]
+    fn test_normalized_only_with_anomalies() {
+        let inputs = ["LLR\n", "LLR\r\n", "LLR \n", "LLR\n\n", "\u{feff}LLR", "LLR\u{a0}", "\n", ""];
+        for input in inputs {
+            let (normalized, anomalies) = normalize(input);
+            assert_eq!(normalized == input, anomalies.is_empty(), "{:?}", input);
+            assert_eq!(check(input).is_ok(), anomalies.is_empty(), "{:?}", input);
+            assert_eq!(normalize(&normalized).1, vec![], "{:?}", input);
+        }
+    }
+}
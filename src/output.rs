@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use crate::answer::Answer;
+use crate::memory::Usage;
+use crate::runner::Report;
+use crate::solver::Part;
+
+/// One line of machine-readable output: the answer to a part, or why there isn't one.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    /// `None` if the whole day failed, e.g. because its input couldn't be read.
+    pub part: Option<Part>,
+    pub answer: Option<Answer>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// `None` unless the counting allocator is installed.
+    pub memory: Option<Usage>,
+    /// Whether the answer was taken from the cache instead of solving the part.
+    pub cached: bool,
+    /// The cached answer, if it differs from the answer of a fresh run.
+    pub cache_mismatch: Option<Answer>,
+    pub error: Option<String>,
+}
+
+impl Record {
+    /// The answer to a part, taken from the cache.
+    pub fn cached(day: u8, part: Part, answer: Answer) -> Record {
+        Record {
+            day,
+            part: Some(part),
+            answer: Some(answer),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            memory: None,
+            cached: true,
+            cache_mismatch: None,
+            error: None,
+        }
+    }
+
+    /// A day that couldn't be solved at all.
+    pub fn failed(day: u8, error: String) -> Record {
+        Record {
+            day,
+            part: None,
+            answer: None,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            memory: None,
+            cached: false,
+            cache_mismatch: None,
+            error: Some(error),
+        }
+    }
+
+    /// Time to get the answer, parsing included.
+    pub fn elapsed(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+}
+
+impl From<&Report> for Record {
+    fn from(report: &Report) -> Self {
+        Record {
+            day: report.day,
+            part: Some(report.part),
+            answer: report.answer.as_ref().ok().cloned().flatten(),
+            parse_time: report.parse_time,
+            solve_time: report.solve_time,
+            memory: report.memory,
+            cached: false,
+            cache_mismatch: None,
+            error: match &report.answer {
+                Ok(Some(_)) => None,
+                Ok(None) => Some("not solved yet".to_string()),
+                Err(error) => Some(error.to_string()),
+            },
+        }
+    }
+}
+
+/// Render records as a JSON array of objects, with the times in nanoseconds and the memory in
+/// bytes. Numeric answers are JSON numbers, text answers are strings.
+///
+/// # Examples:
+///
+/// ```
+/// use aoc2023::output::{json, Record};
+/// let records = [Record::failed(3, "no input".to_string())];
+/// assert_eq!(json(&records), "[\n  {\"day\": 3, \"part\": null, \"answer\": null, \
+///     \"parse_ns\": 0, \"solve_ns\": 0, \"elapsed_ns\": 0, \"allocated_bytes\": null, \
+///     \"peak_bytes\": null, \"cached\": false, \"cache_mismatch\": null, \"error\": \"no input\"}\n]\n");
+/// ```
+pub fn json(records: &[Record]) -> String {
+    let objects: Vec<String> = records.iter()
+        .map(|record| {
+            format!("  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_ns\": {}, \"solve_ns\": {}, \"elapsed_ns\": {}, \"allocated_bytes\": {}, \"peak_bytes\": {}, \"cached\": {}, \"cache_mismatch\": {}, \"error\": {}}}",
+                    record.day,
+                    record.part.map_or("null".to_string(), |part| part.to_string()),
+                    record.answer.as_ref().map_or("null".to_string(), json_answer),
+                    record.parse_time.as_nanos(),
+                    record.solve_time.as_nanos(),
+                    record.elapsed().as_nanos(),
+                    record.memory.map_or("null".to_string(), |memory| memory.allocated.to_string()),
+                    record.memory.map_or("null".to_string(), |memory| memory.peak.to_string()),
+                    record.cached,
+                    record.cache_mismatch.as_ref().map_or("null".to_string(), json_answer),
+                    record.error.as_deref().map_or("null".to_string(), json_string))
+        })
+        .collect();
+
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(_) | Answer::Big(_) => answer.to_string(),
+        Answer::Text(text) => json_string(text),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Render records as CSV with a header row, with the times in nanoseconds and the memory in bytes.
+pub fn csv(records: &[Record]) -> String {
+    let mut csv = "day,part,answer,parse_ns,solve_ns,elapsed_ns,allocated_bytes,peak_bytes,cached,cache_mismatch,error\n".to_string();
+    for record in records {
+        csv.push_str(&format!("{},{},{},{},{},{},{},{},{},{},{}\n",
+                              record.day,
+                              record.part.map_or(String::new(), |part| part.to_string()),
+                              record.answer.as_ref().map_or(String::new(), |answer| csv_field(&answer.to_string())),
+                              record.parse_time.as_nanos(),
+                              record.solve_time.as_nanos(),
+                              record.elapsed().as_nanos(),
+                              record.memory.map_or(String::new(), |memory| memory.allocated.to_string()),
+                              record.memory.map_or(String::new(), |memory| memory.peak.to_string()),
+                              record.cached,
+                              record.cache_mismatch.as_ref().map_or(String::new(), |answer| csv_field(&answer.to_string())),
+                              record.error.as_deref().map_or(String::new(), csv_field)));
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 7,
+                part: Some(Part::One),
+                answer: Some(Answer::Int(6440)),
+                parse_time: Duration::from_nanos(100),
+                solve_time: Duration::from_nanos(250),
+                memory: Some(Usage { allocated: 4096, peak: 1024 }),
+                cached: false,
+                cache_mismatch: Some(Answer::Int(6592)),
+                error: None,
+            },
+            Record::cached(7, Part::Two, Answer::Int(5905)),
+            Record::failed(8, "can't read \"inputs/day_08.txt\", it's missing".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(json(&records()), "\
+[
+  {\"day\": 7, \"part\": 1, \"answer\": 6440, \"parse_ns\": 100, \"solve_ns\": 250, \"elapsed_ns\": 350, \"allocated_bytes\": 4096, \"peak_bytes\": 1024, \"cached\": false, \"cache_mismatch\": 6592, \"error\": null},
+  {\"day\": 7, \"part\": 2, \"answer\": 5905, \"parse_ns\": 0, \"solve_ns\": 0, \"elapsed_ns\": 0, \"allocated_bytes\": null, \"peak_bytes\": null, \"cached\": true, \"cache_mismatch\": null, \"error\": null},
+  {\"day\": 8, \"part\": null, \"answer\": null, \"parse_ns\": 0, \"solve_ns\": 0, \"elapsed_ns\": 0, \"allocated_bytes\": null, \"peak_bytes\": null, \"cached\": false, \"cache_mismatch\": null, \"error\": \"can't read \\\"inputs/day_08.txt\\\", it's missing\"}
+]
+");
+        assert_eq!(json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(csv(&records()), "\
+day,part,answer,parse_ns,solve_ns,elapsed_ns,allocated_bytes,peak_bytes,cached,cache_mismatch,error
+7,1,6440,100,250,350,4096,1024,false,6592,
+7,2,5905,0,0,0,,,true,,
+8,,,0,0,0,,,false,,\"can't read \"\"inputs/day_08.txt\"\", it's missing\"
+");
+    }
+
+    #[test]
+    fn test_json_answer() {
+        assert_eq!(json_answer(&Answer::Big(1 << 70)), "1180591620717411303424");
+        assert_eq!(json_answer(&Answer::from("say \"hi\"")), "\"say \\\"hi\\\"\"");
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("tab\there\u{1}"), "\"tab\\there\\u0001\"");
+    }
+}
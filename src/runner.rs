@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::cancel::{self, Token};
+use crate::error::Error;
+use crate::memory::{self, Usage};
+use crate::normalize::{self, Anomaly, Strictness};
+use crate::solver::{Part, Puzzle};
+use crate::trace;
+
+/// The answer to one part of a day, with the time it took to get it.
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: Part,
+    /// `Ok(None)` if the part isn't solved yet.
+    pub answer: Result<Option<Answer>, Error>,
+    /// Time spent parsing the input. The input is parsed once per `run`, so the parts solved
+    /// together share it.
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// Memory used to parse the input and solve the part, if the counting allocator is installed.
+    pub memory: Option<Usage>,
+    /// How the solver got to the answer, if asked to explain it.
+    pub explanation: Vec<String>,
+    /// What was fixed in the input before parsing it, with [`Strictness::Warn`].
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// How to solve the parts.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// Time each part gets to find its answer, after which it fails with [`Error::TimedOut`].
+    pub timeout: Option<Duration>,
+    /// Collect the explanations of the solvers in the reports.
+    pub explain: bool,
+    /// What to do with CRLF line endings, trailing whitespace and such in the input.
+    pub strictness: Strictness,
+}
+
+/// Parse the input of a day once and solve the given parts on it, timing each stage.
+///
+/// The input is normalized first, see [`normalize`]. Fails if the input can't be parsed, or with
+/// [`Strictness::Error`] if it needs normalizing.
+pub fn run(day: u8, puzzle: &dyn Puzzle, input: &str, parts: &[Part], options: Options) -> Result<Vec<Report>, Error> {
+    if options.strictness == Strictness::Error {
+        normalize::check(input)?;
+    }
+    let (input, mut anomalies) = normalize::normalize(input);
+    if options.strictness != Strictness::Warn {
+        anomalies.clear();
+    }
+
+    let start = Instant::now();
+    let (parsed, parse_memory, retained) = memory::measure(|| puzzle.parse(&input));
+    let parse_time = start.elapsed();
+    let parsed = parsed?;
+
+    let reports = parts.iter()
+        .map(|&part| {
+            let token = options.timeout.map_or(Token::none(), Token::with_timeout);
+            let start = Instant::now();
+            let ((answer, explanation), solve_memory, _) = memory::measure(|| {
+                trace::collect(options.explain, || {
+                    cancel::scope(token, || puzzle.solve(parsed.as_ref(), part))
+                })
+            });
+            let solve_time = start.elapsed();
+            let memory = parse_memory.zip(solve_memory)
+                .map(|(parse, solve)| parse.then(solve, retained));
+            Report { day, part, answer, parse_time, solve_time, memory, explanation, anomalies: anomalies.clone() }
+        })
+        .collect();
+    Ok(reports)
+}
+
+/// Solve all parts of the given days, running the days on up to `jobs` threads at once.
+///
+/// Each day parses its input once, for all of its parts. The results are in the order of the days,
+/// and a day fails if its input can't be parsed.
+pub fn run_all(days: &[(u8, &dyn Puzzle, &str)], jobs: usize, options: Options) -> Vec<Result<Vec<Report>, Error>> {
+    parallel(jobs, days, |&(day, puzzle, input)| run(day, puzzle, input, &Part::ALL, options))
+}
+
+/// Map the items on up to `threads` threads, keeping the order of the items in the results.
+pub fn parallel<I, T, F>(threads: usize, items: &[I], f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(i) {
+                            Some(item) => results.push((i, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter()
+            .flat_map(|worker| worker.join().expect("a solver panicked"))
+            .collect()
+    });
+    results.sort_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_07::{CAMEL_CARDS, Day07};
+    use crate::day_08::Day08;
+    use crate::day_09::{Day09, TEST_REPORT};
+
+    #[test]
+    fn run_reports_every_part() {
+        let reports = run(7, &Day07, CAMEL_CARDS, &Part::ALL, Options::default()).unwrap();
+        let answers: Vec<(u8, Part, Option<Answer>)> = reports.iter()
+            .map(|report| (report.day, report.part, report.answer.as_ref().unwrap().clone()))
+            .collect();
+        assert_eq!(answers, vec![(7, Part::One, Some(Answer::Int(6440))), (7, Part::Two, Some(Answer::Int(5905)))]);
+        assert_eq!(reports[0].parse_time, reports[1].parse_time);
+    }
+
+    #[test]
+    fn run_times_out_a_part() {
+        // `ZZZ` can't be reached, so part 1 walks in circles until the timeout
+        let map = "L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)";
+        let options = Options { timeout: Some(Duration::from_millis(20)), ..Options::default() };
+        let reports = run(8, &Day08, map, &Part::ALL, options).unwrap();
+        assert!(matches!(reports[0].answer, Err(Error::TimedOut(_))));
+        assert!(matches!(reports[1].answer, Err(Error::TimedOut(_))));
+        assert!(reports[1].solve_time >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn run_normalizes_the_input() {
+        let input = "32T3K 765\r\nT55J5 684\r\nKK677 28\r\nKTJJT 220\r\nQQQJA 483\r\n\r\n";
+        let reports = run(7, &Day07, input, &[Part::One], Options::default()).unwrap();
+        assert_eq!(reports[0].answer.as_ref().unwrap(), &Some(Answer::Int(6440)));
+        assert!(reports[0].anomalies.is_empty());
+
+        let options = Options { strictness: Strictness::Warn, ..Options::default() };
+        let reports = run(7, &Day07, input, &[Part::One], options).unwrap();
+        assert_eq!(reports[0].anomalies, vec![
+            Anomaly::CarriageReturns { lines: 6, first: 1 },
+            Anomaly::TrailingBlankLines { lines: 1 },
+        ]);
+
+        let options = Options { strictness: Strictness::Error, ..Options::default() };
+        match run(7, &Day07, input, &[Part::One], options) {
+            Err(Error::Parse(error)) => assert_eq!((error.line, error.column), (1, 10)),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn parallel_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let squares = parallel(4, &items, |&n| {
+            // make the early items finish last
+            thread::sleep(Duration::from_micros(50 - n));
+            n * n
+        });
+        assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<u64>>());
+        assert_eq!(parallel(3, &[] as &[u64], |&n| n), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn run_all_in_day_order() {
+        let days: [(u8, &dyn Puzzle, &str); 2] = [(9, &Day09, TEST_REPORT), (7, &Day07, CAMEL_CARDS)];
+        let results = run_all(&days, 4, Options::default());
+        // both parts of a day share its parsed input
+        for reports in &results {
+            let reports = reports.as_ref().unwrap();
+            assert_eq!(reports[0].parse_time, reports[1].parse_time);
+        }
+        let answers: Vec<Vec<(u8, Part, Option<Answer>)>> = results.into_iter()
+            .map(|reports| reports.unwrap().iter()
+                .map(|report| (report.day, report.part, report.answer.as_ref().unwrap().clone()))
+                .collect())
+            .collect();
+        assert_eq!(answers, vec![
+            vec![(9, Part::One, Some(Answer::Int(114))), (9, Part::Two, Some(Answer::Int(2)))],
+            vec![(7, Part::One, Some(Answer::Int(6440))), (7, Part::Two, Some(Answer::Int(5905)))],
+        ]);
+    }
+}
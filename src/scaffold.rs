@@ -0,0 +1,237 @@
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Source of a new day module with unsolved parts and an ignored example test.
+pub fn module_source(day: u8) -> String {
+    format!(r#"use crate::answer::Answer;
+use crate::aoc::parse;
+use crate::error::{{Error, ParseError}};
+#[allow(unused_imports)] // `Part` is for the examples, once they're filled in
+use crate::solver::{{Example, Part, Solver}};
+
+/// Parse the puzzle input, once for both parts. `aoc::parse` has helpers for numbers, fields,
+/// sections and such, and `aoc::grid` parses maps into a `Grid`.
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {{
+    parse::lines(input, |line| Ok(line.to_string()))
+}}
+
+/// Solve AOC2023 day {day}, Part One.
+/// https://adventofcode.com/2023/day/{day}
+pub fn solve_part_1(_lines: &[String]) -> i64 {{
+    todo!("solve day {day} part 1")
+}}
+
+/// Solve AOC2023 day {day}, Part Two.
+/// https://adventofcode.com/2023/day/{day}#part2
+pub fn solve_part_2(_lines: &[String]) -> i64 {{
+    todo!("solve day {day} part 2")
+}}
+
+/// Example from the puzzle description.
+pub const TEST_INPUT: &str = "\
+";
+
+/// The examples of the puzzle description.
+pub const EXAMPLES: &[Example] = &[
+    // Example {{ part: Part::One, input: TEST_INPUT, answer: "0" }},
+];
+
+/// Solver for AOC2023 day {day}.
+pub struct Day{day:02};
+
+impl Solver for Day{day:02} {{
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {{
+        Ok(parse(input)?)
+    }}
+
+    // answer `Ok(Some(solve_part_1(input).into()))` once it's solved
+    fn part_1(&self, _input: &Self::Input) -> Result<Option<Answer>, Error> {{
+        Ok(None)
+    }}
+
+    // answer `Ok(Some(solve_part_2(input).into()))` once it's solved
+    fn part_2(&self, _input: &Self::Input) -> Result<Option<Answer>, Error> {{
+        Ok(None)
+    }}
+
+    fn examples(&self) -> &'static [Example] {{
+        EXAMPLES
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    #[ignore = "fill in the example of the puzzle"]
+    fn test_part_1() {{
+        assert_eq!(solve_part_1(&parse(TEST_INPUT).unwrap()), 0);
+    }}
+
+    #[test]
+    #[ignore = "fill in the example of the puzzle"]
+    fn test_part_2() {{
+        assert_eq!(solve_part_2(&parse(TEST_INPUT).unwrap()), 0);
+    }}
+}}
+"#)
+}
+
+/// Register a new day module in the source of `lib.rs`: declare the module in `aoc`, re-export it
+/// and add its solver to `DAYS`, keeping each list ordered by day.
+pub fn register(lib_source: &str, day: u8) -> Result<String, String> {
+    let module = format!("day_{:02}", day);
+    let declaration = format!("    pub mod {};", module);
+    if lib_source.lines().any(|line| line == declaration) {
+        return Err(format!("{} is already registered", module));
+    }
+
+    let mut lines: Vec<String> = lib_source.lines().map(String::from).collect();
+
+    // the module declaration, among the others in `pub mod aoc`
+    let declarations: Vec<(usize, u8)> = lines.iter().enumerate()
+        .filter_map(|(i, line)| {
+            line.strip_prefix("    pub mod day_")
+                .and_then(|rest| rest.strip_suffix(';'))
+                .and_then(|n| n.parse::<u8>().ok())
+                .map(|n| (i, n))
+        })
+        .collect();
+    let index = insertion_index(&declarations, day)
+        .ok_or("no day modules declared in `pub mod aoc`")?;
+    lines.insert(index, declaration);
+
+    // the re-export
+    let reexport = lines.iter()
+        .position(|line| line.starts_with("pub use aoc::{"))
+        .ok_or("no `pub use aoc::{..}` of the day modules")?;
+    let mut modules: Vec<String> = lines[reexport]
+        .trim_start_matches("pub use aoc::{")
+        .trim_end_matches("};")
+        .split(", ")
+        .map(String::from)
+        .chain(std::iter::once(module.clone()))
+        .collect();
+    modules.sort();
+    lines[reexport] = format!("pub use aoc::{{{}}};", modules.join(", "));
+
+    // the solver in `DAYS`
+    let entries: Vec<(usize, u8)> = lines.iter().enumerate()
+        .filter_map(|(i, line)| {
+            line.strip_prefix("    (")
+                .and_then(|rest| rest.split_once(", &day_"))
+                .and_then(|(n, _)| n.parse::<u8>().ok())
+                .map(|n| (i, n))
+        })
+        .collect();
+    let index = insertion_index(&entries, day).ok_or("no solvers listed in `DAYS`")?;
+    lines.insert(index, format!("    ({}, &{}::Day{:02}),", day, module, day));
+
+    let mut source = lines.join("\n");
+    if lib_source.ends_with('\n') {
+        source.push('\n');
+    }
+    Ok(source)
+}
+
+/// Index of the line to insert a day at, given the indices and days of an ordered list of lines.
+fn insertion_index(lines: &[(usize, u8)], day: u8) -> Option<usize> {
+    match lines.iter().find(|&&(_, d)| d > day) {
+        Some(&(i, _)) => Some(i),
+        None => lines.last().map(|&(i, _)| i + 1),
+    }
+}
+
+/// Create the module of a new day in the crate at `root` and register it in `lib.rs`.
+///
+/// Returns the path of the new module.
+pub fn scaffold(root: &Path, day: u8) -> io::Result<PathBuf> {
+    let module_path = root.join("src").join("aoc").join(format!("day_{:02}.rs", day));
+    if module_path.exists() {
+        return Err(io::Error::new(ErrorKind::AlreadyExists,
+                                  format!("{} already exists", module_path.display())));
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let lib_source = fs::read_to_string(&lib_path)?;
+    let lib_source = register(lib_source.as_str(), day)
+        .map_err(|message| io::Error::new(ErrorKind::InvalidData, message))?;
+
+    fs::write(&module_path, module_source(day))?;
+    fs::write(&lib_path, lib_source)?;
+    Ok(module_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod aoc {
+    pub mod day_01;
+    pub mod day_03;
+}
+pub mod solver;
+
+pub use aoc::{day_01, day_03};
+
+pub static DAYS: &[(u8, &dyn Puzzle)] = &[
+    (1, &day_01::Day01),
+    (3, &day_03::Day03),
+];
+";
+
+    #[test]
+    fn test_register_in_order() {
+        assert_eq!(register(LIB, 2).unwrap(), "\
+pub mod aoc {
+    pub mod day_01;
+    pub mod day_02;
+    pub mod day_03;
+}
+pub mod solver;
+
+pub use aoc::{day_01, day_02, day_03};
+
+pub static DAYS: &[(u8, &dyn Puzzle)] = &[
+    (1, &day_01::Day01),
+    (2, &day_02::Day02),
+    (3, &day_03::Day03),
+];
+");
+    }
+
+    #[test]
+    fn test_register_last() {
+        let source = register(LIB, 10).unwrap();
+        assert!(source.contains("    pub mod day_03;\n    pub mod day_10;\n}"));
+        assert!(source.contains("pub use aoc::{day_01, day_03, day_10};"));
+        assert!(source.contains("    (3, &day_03::Day03),\n    (10, &day_10::Day10),\n];"));
+    }
+
+    #[test]
+    fn test_module_source_imports_part() {
+        // uncommenting the example needs `Part`
+        let source = module_source(20);
+        assert!(source.contains("// Example { part: Part::One,"));
+        assert!(source.contains("use crate::solver::{Example, Part, Solver};"));
+    }
+
+    #[test]
+    fn test_register_twice() {
+        assert_eq!(register(LIB, 3), Err("day_03 is already registered".to_string()));
+    }
+
+    #[test]
+    fn test_register_current_lib() {
+        let day = (1..=25).find(|&day| crate::puzzle(day).is_none()).unwrap();
+        let source = register(include_str!("lib.rs"), day).unwrap();
+        assert!(source.contains(&format!("    pub mod day_{:02};\n", day)));
+        assert!(source.contains(&format!("({}, &day_{:02}::Day{:02}),", day, day, day)));
+    }
+}
@@ -0,0 +1,143 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::answer::Answer;
+use crate::error::Error;
+
+/// One of the two parts of a daily puzzle.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+/// An example input from the puzzle description, with the expected answer to one of the parts.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Example {
+    pub part: Part,
+    pub input: &'static str,
+    /// The answer as the puzzle description shows it.
+    pub answer: &'static str,
+}
+
+/// The solution of a daily puzzle: a parse step whose output is shared by both parts.
+///
+/// A part that isn't solved yet answers `Ok(None)`.
+pub trait Solver {
+    /// The puzzle input after parsing.
+    type Input: 'static;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+
+    fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error>;
+
+    fn part_2(&self, input: &Self::Input) -> Result<Option<Answer>, Error>;
+
+    /// The examples of the puzzle description.
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    /// Version of the solver. Change it when a change of the solver may change its answers, so
+    /// that their cached answers aren't used anymore.
+    fn version(&self) -> &'static str {
+        "1"
+    }
+}
+
+/// A [`Solver`] with its input type erased, so that all days can be kept in one registry.
+pub trait Puzzle: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, Error>;
+
+    /// Solve a part of the puzzle for an input returned by [`Puzzle::parse`].
+    ///
+    /// # Panics
+    ///
+    /// If the input was parsed by another puzzle.
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Option<Answer>, Error>;
+
+    fn examples(&self) -> &'static [Example];
+
+    fn version(&self) -> &'static str;
+}
+
+impl<S: Solver + Sync> Puzzle for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, Error> {
+        let input = Solver::parse(self, input)?;
+        Ok(Box::new(input))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Option<Answer>, Error> {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("input was parsed by another puzzle");
+        match part {
+            Part::One => self.part_1(input),
+            Part::Two => self.part_2(input),
+        }
+    }
+
+    fn version(&self) -> &'static str {
+        Solver::version(self)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Solver::examples(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+
+    struct Lines;
+
+    impl Solver for Lines {
+        type Input = Vec<String>;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+            match input {
+                "" => Err(Error::Parse(ParseError::at(input, input, "a line"))),
+                input => Ok(input.lines().map(String::from).collect()),
+            }
+        }
+
+        fn part_1(&self, input: &Self::Input) -> Result<Option<Answer>, Error> {
+            Ok(Some(input.len().into()))
+        }
+
+        fn part_2(&self, _input: &Self::Input) -> Result<Option<Answer>, Error> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn puzzle_solves_parsed_input() {
+        let puzzle: &dyn Puzzle = &Lines;
+        let input = puzzle.parse("a\nb\nc").unwrap();
+        assert_eq!(puzzle.solve(input.as_ref(), Part::One).unwrap(), Some(Answer::Int(3)));
+        assert_eq!(puzzle.solve(input.as_ref(), Part::Two).unwrap(), None);
+    }
+
+    #[test]
+    fn puzzle_parse_error() {
+        let puzzle: &dyn Puzzle = &Lines;
+        match puzzle.parse("") {
+            Err(Error::Parse(error)) => assert_eq!(error.expected, "a line"),
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static NOTES: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, collecting what it [`explain`]s on the current thread if `enabled`.
+pub fn collect<T>(enabled: bool, f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let previous = NOTES.replace(enabled.then(Vec::new));
+    let result = f();
+    let notes = NOTES.replace(previous).unwrap_or_default();
+    (result, notes)
+}
+
+/// Note a step of the reasoning of a solver, e.g. why an item was accepted. The note is only
+/// written when explaining, so it costs nothing otherwise.
+pub fn explain(note: impl FnOnce() -> String) {
+    NOTES.with_borrow_mut(|notes| {
+        if let Some(notes) = notes {
+            notes.push(note());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let (answer, notes) = collect(true, || {
+            explain(|| "first".to_string());
+            explain(|| format!("second is {}", 2));
+            42
+        });
+        assert_eq!(answer, 42);
+        assert_eq!(notes, vec!["first", "second is 2"]);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let (_, notes) = collect(false, || explain(|| panic!("not explaining")));
+        assert!(notes.is_empty());
+        explain(|| panic!("not collecting"));
+    }
+}
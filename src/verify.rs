@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::answer::Answer;
+use crate::solver::Part;
+
+/// File with the expected answers, unless another one is given.
+pub const DEFAULT_ANSWERS: &str = "answers.txt";
+
+/// Expected answers per day and part.
+///
+/// Stored as text, one answer per line: `<day> <part> <answer>`. The answer is the rest of the
+/// line, so text answers may have spaces. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    expected: BTreeMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> io::Result<Answers> {
+        let contents = fs::read_to_string(path)?;
+        Answers::parse(contents.as_str())
+            .map_err(|message| io::Error::new(ErrorKind::InvalidData, message))
+    }
+
+    fn parse(contents: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || format!("invalid answers line {}: {}", i + 1, line);
+            let (day, rest) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let (part, answer) = rest.trim_start().split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let day = day.parse::<u8>().map_err(|_| invalid())?;
+            let part = match part {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return Err(invalid()),
+            };
+            answers.expected.insert((day, part), answer.trim_start().to_string());
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.expected.get(&(day, part)).map(String::as_str)
+    }
+}
+
+/// The result of checking an answer against the expected one.
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, actual: Option<Answer> },
+    /// There is no expected answer to check against.
+    Missing,
+}
+
+impl Status {
+    pub fn check(expected: Option<&str>, actual: Option<Answer>) -> Status {
+        match (expected, actual) {
+            (None, _) => Status::Missing,
+            (Some(expected), Some(actual)) if expected == actual.to_string() => Status::Pass,
+            (Some(expected), actual) => Status::Fail { expected: expected.to_string(), actual },
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual: Some(actual) } => {
+                write!(f, "FAIL: expected {}, got {}", expected, actual)
+            }
+            Status::Fail { expected, actual: None } => {
+                write!(f, "FAIL: expected {}, not solved yet", expected)
+            }
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse("\
+# day part answer
+5 1 35
+5 2 46
+
+7 1 6440
+10 2 two words").unwrap();
+        assert_eq!(answers.get(5, Part::One), Some("35"));
+        assert_eq!(answers.get(5, Part::Two), Some("46"));
+        assert_eq!(answers.get(7, Part::One), Some("6440"));
+        assert_eq!(answers.get(7, Part::Two), None);
+        assert_eq!(answers.get(10, Part::Two), Some("two words"));
+    }
+
+    #[test]
+    fn test_parse_invalid_answers() {
+        assert_eq!(Answers::parse("5 3 35"), Err("invalid answers line 1: 5 3 35".to_string()));
+        assert_eq!(Answers::parse("5 1\n"), Err("invalid answers line 1: 5 1".to_string()));
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Status::check(Some("35"), Some(Answer::Int(35))), Status::Pass);
+        assert_eq!(Status::check(Some("LJKRA"), Some(Answer::from("LJKRA"))), Status::Pass);
+        assert_eq!(Status::check(Some("two words"), Some(Answer::from("two words"))), Status::Pass);
+        assert_eq!(Status::check(Some("35"), Some(Answer::Int(36))),
+                   Status::Fail { expected: "35".to_string(), actual: Some(Answer::Int(36)) });
+        assert_eq!(Status::check(Some("35"), None),
+                   Status::Fail { expected: "35".to_string(), actual: None });
+        assert_eq!(Status::check(None, Some(Answer::Int(35))), Status::Missing);
+    }
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::output::Record;
+
+/// Polls a file for changes of its modification time or contents.
+pub struct Watcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    contents: Option<String>,
+}
+
+impl Watcher {
+    pub fn new(path: &Path) -> Watcher {
+        Watcher { path: path.to_path_buf(), modified: None, contents: None }
+    }
+
+    /// The contents of the file if it changed since the last poll, or on the first poll.
+    pub fn poll(&mut self) -> io::Result<Option<String>> {
+        let modified = fs::metadata(&self.path)?.modified().ok();
+        let contents = fs::read_to_string(&self.path)?;
+        if self.contents.is_some() && modified == self.modified && self.contents.as_ref() == Some(&contents) {
+            return Ok(None);
+        }
+
+        self.modified = modified;
+        self.contents = Some(contents.clone());
+        Ok(Some(contents))
+    }
+}
+
+/// Describe the answers of a run compared to the previous run, a line per record.
+pub fn diff(previous: &[Record], current: &[Record]) -> Vec<String> {
+    current.iter()
+        .map(|record| {
+            let label = match record.part {
+                Some(part) => format!("Part {}", part),
+                None => format!("Day {}", record.day),
+            };
+            let after = describe(record);
+            match previous.iter().find(|r| r.day == record.day && r.part == record.part).map(describe) {
+                None => format!("{}: {}", label, after),
+                Some(before) if before == after => format!("{}: {} (unchanged)", label, after),
+                Some(before) => format!("{}: {} -> {}", label, before, after),
+            }
+        })
+        .collect()
+}
+
+fn describe(record: &Record) -> String {
+    match (&record.answer, &record.error) {
+        (Some(answer), _) => answer.to_string(),
+        (None, error) => error.clone().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::answer::Answer;
+    use crate::solver::Part;
+
+    fn record(part: Part, answer: Option<i64>) -> Record {
+        Record {
+            day: 9,
+            part: Some(part),
+            answer: answer.map(Answer::Int),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            memory: None,
+            cached: false,
+            cache_mismatch: None,
+            error: answer.map_or(Some("not solved yet".to_string()), |_| None),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = vec![record(Part::One, Some(114)), record(Part::Two, None)];
+        assert_eq!(diff(&[], &first), vec!["Part 1: 114", "Part 2: not solved yet"]);
+
+        let second = vec![record(Part::One, Some(114)), record(Part::Two, Some(2))];
+        assert_eq!(diff(&first, &second), vec!["Part 1: 114 (unchanged)", "Part 2: not solved yet -> 2"]);
+    }
+
+    #[test]
+    fn test_poll() {
+        let path = env::temp_dir().join(format!("aoc2023-watch-{}.txt", std::process::id()));
+        fs::write(&path, "0 3 6 9 12 15").unwrap();
+
+        let mut watcher = Watcher::new(&path);
+        assert_eq!(watcher.poll().unwrap(), Some("0 3 6 9 12 15".to_string()));
+        assert_eq!(watcher.poll().unwrap(), None);
+
+        fs::write(&path, "1 3 6 10 15 21").unwrap();
+        assert_eq!(watcher.poll().unwrap(), Some("1 3 6 10 15 21".to_string()));
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll().is_err());
+    }
+}